@@ -0,0 +1,168 @@
+use aoc_utils_pattern::{parse_pattern, Segment};
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::{
+    parse_macro_input, Data, DeriveInput, Error, Fields, GenericArgument, LitStr, PathArguments,
+    Type,
+};
+
+/// Derives `FromStr` for a struct with named fields from a
+/// `#[pattern("...")]` attribute, e.g.
+///
+/// ```ignore
+/// #[derive(FromInput)]
+/// #[pattern("{name} = ({left}, {right})")]
+/// struct Node {
+///     name: String,
+///     left: String,
+///     right: String,
+/// }
+/// ```
+///
+/// Each `{field}` in the pattern is parsed with the field type's `FromStr`
+/// impl. The text in between is matched token by token using
+/// `aoc_utils::Tokenizer`, so spacing doesn't matter. A field runs up to the
+/// first token of the text that follows it, or, if only whitespace follows
+/// it, up to the next whitespace.
+///
+/// `Vec<T>` fields are split on whitespace, or on the string given by a
+/// `#[separator("...")]` attribute on the field, and each element is parsed
+/// with `T`'s `FromStr` impl.
+///
+/// The error type is `aoc_utils::ScanError`.
+#[proc_macro_derive(FromInput, attributes(pattern, separator))]
+pub fn derive_from_input(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+// Returns the element type if `ty` is a `Vec<T>`
+fn vec_element(ty: &Type) -> Option<&Type> {
+    let Type::Path(path) = ty else {
+        return None;
+    };
+    let segment = path.path.segments.last()?;
+    if segment.ident != "Vec" {
+        return None;
+    }
+    let PathArguments::AngleBracketed(arguments) = &segment.arguments else {
+        return None;
+    };
+    match arguments.args.first()? {
+        GenericArgument::Type(element) => Some(element),
+        _ => None,
+    }
+}
+
+fn expand(input: DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let name = &input.ident;
+
+    let Data::Struct(data) = &input.data else {
+        return Err(Error::new_spanned(name, "FromInput only supports structs"));
+    };
+    let Fields::Named(fields) = &data.fields else {
+        return Err(Error::new_spanned(
+            name,
+            "FromInput only supports structs with named fields",
+        ));
+    };
+
+    let Some(attribute) = input.attrs.iter().find(|a| a.path().is_ident("pattern")) else {
+        return Err(Error::new_spanned(
+            name,
+            r#"missing #[pattern("...")] attribute"#,
+        ));
+    };
+    let pattern: LitStr = attribute.parse_args()?;
+    let text = pattern.value();
+    let segments = parse_pattern(&text).map_err(|message| Error::new(pattern.span(), message))?;
+
+    // Every field of the struct has to appear in the pattern exactly once
+    for field in fields.named.iter() {
+        let ident = field.ident.as_ref().unwrap();
+        let count = segments
+            .iter()
+            .filter(|segment| matches!(segment, Segment::Field(f) if ident == f))
+            .count();
+        if count != 1 {
+            let message = format!("`{ident}` must appear exactly once in the pattern");
+            return Err(Error::new_spanned(ident, message));
+        }
+    }
+
+    let mut steps = Vec::new();
+    for (index, segment) in segments.iter().enumerate() {
+        match segment {
+            Segment::Literal(literal) => steps.push(quote! {
+                ::aoc_utils::from_input::literal(&mut __tokens, __input, #literal)?;
+            }),
+            Segment::Field(field_name) => {
+                let Some(field) = fields
+                    .named
+                    .iter()
+                    .find(|f| f.ident.as_ref().unwrap() == field_name)
+                else {
+                    let message = format!("the struct has no field named `{field_name}`");
+                    return Err(Error::new(pattern.span(), message));
+                };
+                let ident = field.ident.as_ref().unwrap();
+                let ty = &field.ty;
+
+                let terminator = match segments.get(index + 1) {
+                    Some(Segment::Literal(literal)) => quote! {
+                        ::aoc_utils::from_input::Terminator::before(#literal)
+                    },
+                    _ => quote! { ::aoc_utils::from_input::Terminator::End },
+                };
+
+                let value = match vec_element(ty) {
+                    Some(element) => {
+                        let separator =
+                            match field.attrs.iter().find(|a| a.path().is_ident("separator")) {
+                                Some(attribute) => {
+                                    let separator: LitStr = attribute.parse_args()?;
+                                    quote! { ::std::option::Option::Some(#separator) }
+                                }
+                                None => quote! { ::std::option::Option::None },
+                            };
+                        quote! {
+                            ::aoc_utils::from_input::parse_list::<#element>(
+                                stringify!(#ident), position, text, #separator,
+                            )?
+                        }
+                    }
+                    None => quote! {
+                        ::aoc_utils::from_input::parse::<#ty>(stringify!(#ident), position, text)?
+                    },
+                };
+
+                steps.push(quote! {
+                    let #ident: #ty = {
+                        let (position, text) = ::aoc_utils::from_input::field(
+                            &mut __tokens, __input, stringify!(#ident), #terminator,
+                        )?;
+                        #value
+                    };
+                });
+            }
+        }
+    }
+
+    let field_names = fields.named.iter().map(|f| f.ident.as_ref().unwrap());
+    let (impl_generics, type_generics, where_clause) = input.generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics ::std::str::FromStr for #name #type_generics #where_clause {
+            type Err = ::aoc_utils::ScanError;
+
+            fn from_str(__input: &str) -> ::std::result::Result<Self, Self::Err> {
+                let mut __tokens = ::aoc_utils::Tokenizer::new(__input);
+                #(#steps)*
+                ::aoc_utils::from_input::end(&mut __tokens)?;
+                ::std::result::Result::Ok(#name { #(#field_names),* })
+            }
+        }
+    })
+}
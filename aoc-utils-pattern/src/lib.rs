@@ -0,0 +1,65 @@
+//! Splits `scan!` and `#[derive(FromInput)]` patterns into literal text and
+//! `{field}` names. It's a crate of its own so that `aoc-utils` and the derive
+//! macro in `aoc-utils-derive` can both use it.
+
+/// A piece of a pattern.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Segment<'p> {
+    /// Text to match
+    Literal(&'p str),
+    /// The name of a `{field}`, trimmed
+    Field(&'p str),
+}
+
+/// Splits a pattern into segments, failing if a field isn't closed or two
+/// fields are next to each other.
+pub fn parse_pattern(pattern: &str) -> Result<Vec<Segment<'_>>, String> {
+    let mut segments = Vec::new();
+    let mut rest = pattern;
+    while !rest.is_empty() {
+        match rest.find('{') {
+            Some(0) => {
+                let Some(end) = rest.find('}') else {
+                    return Err(format!(r#"unclosed field in pattern "{pattern}""#));
+                };
+                if let Some(Segment::Field(_)) = segments.last() {
+                    return Err(format!(
+                        r#"fields must be separated by text in pattern "{pattern}""#
+                    ));
+                }
+                segments.push(Segment::Field(rest[1..end].trim()));
+                rest = &rest[end + 1..];
+            }
+            Some(start) => {
+                segments.push(Segment::Literal(&rest[..start]));
+                rest = &rest[start..];
+            }
+            None => {
+                segments.push(Segment::Literal(rest));
+                rest = "";
+            }
+        }
+    }
+    Ok(segments)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_pattern() {
+        assert_eq!(
+            parse_pattern("Game {id}: { rest }"),
+            Ok(vec![
+                Segment::Literal("Game "),
+                Segment::Field("id"),
+                Segment::Literal(": "),
+                Segment::Field("rest"),
+            ])
+        );
+        assert_eq!(parse_pattern(""), Ok(vec![]));
+        assert!(parse_pattern("{a} {b").is_err());
+        assert!(parse_pattern("{a}{b}").is_err());
+    }
+}
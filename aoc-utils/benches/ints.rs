@@ -0,0 +1,53 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+use aoc_utils::{ints, ints_iter};
+
+// Lines shaped like the day 4 cards, with the usual mix of labels,
+// punctuation and padded numbers
+fn input() -> String {
+    (1..=1000)
+        .map(|card| {
+            let winning: Vec<String> = (0..10)
+                .map(|i| format!("{:2}", (card * 7 + i) % 100))
+                .collect();
+            let ours: Vec<String> = (0..25)
+                .map(|i| format!("{:2}", (card * 13 + i) % 100))
+                .collect();
+            format!(
+                "Card {card:4}: {} | {}\n",
+                winning.join(" "),
+                ours.join(" ")
+            )
+        })
+        .collect()
+}
+
+fn split_parse(input: &str) -> u64 {
+    input
+        .lines()
+        .map(|line| {
+            let (_, numbers) = line.split_once(':').unwrap();
+            numbers
+                .split_ascii_whitespace()
+                .filter(|word| *word != "|")
+                .map(|word| word.parse::<u64>().unwrap())
+                .sum::<u64>()
+        })
+        .sum()
+}
+
+fn bench_ints(c: &mut Criterion) {
+    let input = input();
+    let mut group = c.benchmark_group("ints");
+    group.bench_function("split_parse", |b| b.iter(|| split_parse(black_box(&input))));
+    group.bench_function("ints", |b| {
+        b.iter(|| ints::<u64>(black_box(&input)).into_iter().sum::<u64>())
+    });
+    group.bench_function("ints_iter", |b| {
+        b.iter(|| ints_iter::<u64>(black_box(&input)).sum::<u64>())
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_ints);
+criterion_main!(benches);
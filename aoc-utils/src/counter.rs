@@ -0,0 +1,165 @@
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// A multiset that counts how many times each item has been added.
+///
+/// Items are remembered in the order they were first added, which is used to
+/// break ties between items with the same count.
+#[derive(Clone, Debug)]
+pub struct Counter<T> {
+    indexes: HashMap<T, usize>,
+    entries: Vec<(T, usize)>,
+}
+
+impl<T: Clone + Eq + Hash> Counter<T> {
+    pub fn new() -> Self {
+        Counter {
+            indexes: HashMap::new(),
+            entries: Vec::new(),
+        }
+    }
+
+    /// Adds one occurrence of `item`.
+    pub fn add(&mut self, item: T) {
+        self.add_n(item, 1);
+    }
+
+    /// Adds `n` occurrences of `item`.
+    pub fn add_n(&mut self, item: T, n: usize) {
+        match self.indexes.get(&item) {
+            Some(&index) => self.entries[index].1 += n,
+            None => {
+                self.indexes.insert(item.clone(), self.entries.len());
+                self.entries.push((item, n));
+            }
+        }
+    }
+
+    /// Removes `item` entirely, returning how many times it had been added.
+    pub fn remove(&mut self, item: &T) -> usize {
+        let Some(index) = self.indexes.remove(item) else {
+            return 0;
+        };
+        let (_, count) = self.entries.remove(index);
+        for (item, _) in &self.entries[index..] {
+            *self.indexes.get_mut(item).unwrap() -= 1;
+        }
+        count
+    }
+
+    /// Returns how many times `item` has been added.
+    pub fn count(&self, item: &T) -> usize {
+        self.indexes
+            .get(item)
+            .map_or(0, |&index| self.entries[index].1)
+    }
+
+    /// Returns the number of distinct items.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns the total number of occurrences of all items.
+    pub fn total(&self) -> usize {
+        self.entries.iter().map(|(_, count)| count).sum()
+    }
+
+    /// Iterates over the items and their counts in the order the items were
+    /// first added.
+    pub fn iter(&self) -> impl Iterator<Item = (&T, usize)> {
+        self.entries.iter().map(|(item, count)| (item, *count))
+    }
+
+    /// Returns the items and their counts, most common first.
+    pub fn most_common(&self) -> Vec<(&T, usize)> {
+        let mut items: Vec<(&T, usize)> = self.iter().collect();
+        items.sort_by_key(|&(_, count)| Reverse(count));
+        items
+    }
+
+    /// Returns just the counts, largest first. Two counters have the same
+    /// signature when they have the same shape, regardless of which items
+    /// they contain, e.g. "AAKKQ" and "2332J" both give `[2, 2, 1]`.
+    pub fn signature(&self) -> Vec<usize> {
+        let mut counts: Vec<usize> = self.entries.iter().map(|(_, count)| *count).collect();
+        counts.sort_by_key(|&count| Reverse(count));
+        counts
+    }
+}
+
+impl<T: Clone + Eq + Hash> Default for Counter<T> {
+    fn default() -> Self {
+        Counter::new()
+    }
+}
+
+impl<T: Clone + Eq + Hash> FromIterator<T> for Counter<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut counter = Counter::new();
+        counter.extend(iter);
+        counter
+    }
+}
+
+impl<T: Clone + Eq + Hash> Extend<T> for Counter<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for item in iter {
+            self.add(item);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_count() {
+        let counter: Counter<char> = "abracadabra".chars().collect();
+        assert_eq!(counter.count(&'a'), 5);
+        assert_eq!(counter.count(&'b'), 2);
+        assert_eq!(counter.count(&'c'), 1);
+        assert_eq!(counter.count(&'z'), 0);
+        assert_eq!(counter.len(), 5);
+        assert_eq!(counter.total(), 11);
+    }
+
+    #[test]
+    fn test_most_common() {
+        let counter: Counter<char> = "abracadabra".chars().collect();
+        let expected = vec![(&'a', 5), (&'b', 2), (&'r', 2), (&'c', 1), (&'d', 1)];
+        assert_eq!(counter.most_common(), expected);
+    }
+
+    #[test]
+    fn test_signature() {
+        let counter: Counter<char> = "32T3K".chars().collect();
+        assert_eq!(counter.signature(), vec![2, 1, 1, 1]);
+
+        let counter: Counter<char> = "AAKKQ".chars().collect();
+        assert_eq!(counter.signature(), vec![2, 2, 1]);
+
+        let counter: Counter<char> = "".chars().collect();
+        assert_eq!(counter.signature(), Vec::<usize>::new());
+    }
+
+    #[test]
+    fn test_remove() {
+        let mut counter: Counter<char> = "KTJJT".chars().collect();
+        assert_eq!(counter.remove(&'J'), 2);
+        assert_eq!(counter.remove(&'J'), 0);
+        assert_eq!(counter.count(&'T'), 2);
+        assert_eq!(counter.count(&'K'), 1);
+        assert_eq!(counter.total(), 3);
+
+        counter.add('J');
+        counter.add_n('K', 3);
+        let expected = vec![(&'K', 4), (&'T', 2), (&'J', 1)];
+        assert_eq!(counter.most_common(), expected);
+    }
+}
@@ -0,0 +1,137 @@
+/// Removes the indentation shared by every line of `text`, so examples can
+/// be written as indented string literals in tests:
+///
+/// ```
+/// use aoc_utils::dedent;
+///
+/// let input = dedent(
+///     "
+///     RL
+///
+///       AAA = (BBB, CCC)
+///     ",
+/// );
+/// assert_eq!(input, "RL\n\n  AAA = (BBB, CCC)\n");
+/// ```
+///
+/// A newline at the very start is removed, as is a last line holding only
+/// whitespace. Blank lines don't count towards the shared indentation and
+/// come out empty. Anything beyond the shared indentation is kept, including
+/// trailing spaces, so it's safe for inputs where whitespace matters.
+pub fn dedent(text: &str) -> String {
+    let text = text
+        .strip_prefix("\r\n")
+        .or_else(|| text.strip_prefix('\n'))
+        .unwrap_or(text);
+
+    let mut lines: Vec<&str> = text.split('\n').collect();
+    if lines.last().is_some_and(|line| line.trim().is_empty()) {
+        // The final line ending stays, but the closing quote's indentation
+        // doesn't
+        *lines.last_mut().unwrap() = "";
+    }
+
+    // Compare whole prefixes rather than counting, so that tabs and spaces
+    // aren't treated as interchangeable
+    let indent = lines
+        .iter()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| &line[..line.len() - line.trim_start().len()])
+        .reduce(|shared, indent| {
+            let length = shared
+                .char_indices()
+                .zip(indent.chars())
+                .find(|((_, a), b)| a != b)
+                .map_or(shared.len().min(indent.len()), |((i, _), _)| i);
+            &shared[..length]
+        })
+        .unwrap_or("");
+
+    lines
+        .iter()
+        .map(|line| {
+            if line.trim().is_empty() {
+                line.strip_suffix('\r').map_or("", |_| "\r")
+            } else {
+                &line[indent.len()..]
+            }
+        })
+        .collect::<Vec<&str>>()
+        .join("\n")
+}
+
+/// Like `dedent`, but gives a `&'static str` that can be passed straight to
+/// a function taking the puzzle input. The string is leaked, so this is for
+/// tests.
+///
+/// ```
+/// use aoc_utils::example;
+///
+/// let input: &str = example!(
+///     "
+///     .....
+///     .S-7.
+///     "
+/// );
+/// assert_eq!(input, ".....\n.S-7.\n");
+/// ```
+#[macro_export]
+macro_rules! example {
+    ($text:expr $(,)?) => {
+        &*::std::boxed::Box::leak($crate::dedent($text).into_boxed_str())
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dedent() {
+        let text = "
+            7-F7-
+            .FJ|7
+
+              SJLL7
+            ";
+        assert_eq!(dedent(text), "7-F7-\n.FJ|7\n\n  SJLL7\n");
+    }
+
+    #[test]
+    fn test_no_leading_newline() {
+        assert_eq!(dedent("a\n  b"), "a\n  b");
+        assert_eq!(dedent("  a\n    b"), "a\n  b");
+        assert_eq!(dedent(""), "");
+    }
+
+    #[test]
+    fn test_significant_whitespace() {
+        // Trailing spaces and extra indentation survive, blank lines that
+        // only hold indentation don't
+        let text = "
+            #  .  \n            \n              #
+            ";
+        assert_eq!(dedent(text), "#  .  \n\n  #\n");
+    }
+
+    #[test]
+    fn test_mixed_indentation() {
+        assert_eq!(dedent("\t  a\n\t b"), " a\nb");
+        assert_eq!(dedent("\r\n    a\r\n    b\r\n    "), "a\r\nb\r\n");
+    }
+
+    #[test]
+    fn test_example() {
+        let input: &'static str = example!(
+            "
+            RL
+
+            AAA = (BBB, CCC)
+            "
+        );
+        assert_eq!(
+            input.lines().collect::<Vec<&str>>(),
+            ["RL", "", "AAA = (BBB, CCC)"]
+        );
+    }
+}
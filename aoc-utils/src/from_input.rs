@@ -0,0 +1,228 @@
+//! Runtime support for `#[derive(FromInput)]`. The generated `FromStr` impls
+//! call these functions, so they're public, but they aren't meant to be used
+//! directly.
+
+use std::fmt::Display;
+use std::str::FromStr;
+
+use crate::{ScanError, Tokenizer};
+
+/// Where the text of a field ends.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Terminator<'p> {
+    /// Just before the tokens of this literal, which has at least one
+    Literal(&'p str),
+    /// At the next whitespace
+    Whitespace,
+    /// At the end of the input
+    End,
+}
+
+impl<'p> Terminator<'p> {
+    /// Returns the terminator for a field followed by `literal` in the
+    /// pattern.
+    pub fn before(literal: &'p str) -> Self {
+        match Tokenizer::new(literal).next() {
+            Some(_) => Terminator::Literal(literal),
+            None => Terminator::Whitespace,
+        }
+    }
+
+    // Returns whether the next tokens are the whole literal, so that a
+    // field's text can include a token the literal starts with, e.g. the
+    // `-` of `-5` before `->`
+    fn is_next(&self, tokens: &mut Tokenizer) -> bool {
+        let Terminator::Literal(literal) = self else {
+            return false;
+        };
+        let checkpoint = tokens.checkpoint();
+        let found = Tokenizer::new(literal).all(|expected| {
+            tokens
+                .next()
+                .is_some_and(|token| token.text == expected.text)
+        });
+        tokens.rewind(checkpoint);
+        found
+    }
+}
+
+/// Consumes the tokens of `literal`, failing if the input has anything else.
+pub fn literal(tokens: &mut Tokenizer, input: &str, literal: &str) -> Result<(), ScanError> {
+    for expected in Tokenizer::new(literal) {
+        match tokens.next() {
+            Some(token) if token.text == expected.text => {}
+            Some(token) => {
+                let message = format!(r#"expected "{}", got "{}""#, expected.text, token.text);
+                return Err(ScanError::new(token.span.start, message));
+            }
+            None => {
+                let message = format!(r#"expected "{}""#, expected.text);
+                return Err(ScanError::new(input.len(), message));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Consumes the tokens making up the field `name`, and returns where the
+/// field starts and its text.
+pub fn field<'a>(
+    tokens: &mut Tokenizer<'a>,
+    input: &'a str,
+    name: &str,
+    terminator: Terminator,
+) -> Result<(usize, &'a str), ScanError> {
+    let first = match tokens.peek() {
+        Some(token) if !terminator.is_next(tokens) => token,
+        Some(token) => {
+            let message = format!("expected a value for `{name}`");
+            return Err(ScanError::new(token.span.start, message));
+        }
+        None => {
+            let message = format!("expected a value for `{name}`");
+            return Err(ScanError::new(input.len(), message));
+        }
+    };
+    tokens.next();
+
+    let mut end = first.span.end;
+    while !terminator.is_next(tokens) {
+        match (terminator, tokens.peek()) {
+            (Terminator::Literal(literal), None) => {
+                let message = format!(r#"expected "{}" after `{name}`"#, literal.trim());
+                return Err(ScanError::new(first.span.start, message));
+            }
+            (_, None) => break,
+            (Terminator::Whitespace, Some(token)) if token.span.start != end => break,
+            (_, Some(token)) => {
+                end = token.span.end;
+                tokens.next();
+            }
+        }
+    }
+
+    Ok((first.span.start, &input[first.span.start..end]))
+}
+
+/// Parses the text of the field `name`, which starts at `position`.
+pub fn parse<T>(name: &str, position: usize, text: &str) -> Result<T, ScanError>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    text.parse().map_err(|error| {
+        let message = format!(r#"bad value for `{name}`: "{text}": {error}"#);
+        ScanError::new(position, message)
+    })
+}
+
+/// Parses the text of the list field `name`, which starts at `position`,
+/// splitting it on `separator`, or on whitespace if there isn't one.
+pub fn parse_list<T>(
+    name: &str,
+    position: usize,
+    text: &str,
+    separator: Option<&str>,
+) -> Result<Vec<T>, ScanError>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    let elements: Vec<&str> = match separator {
+        Some(separator) => text.split(separator).map(str::trim).collect(),
+        None => text.split_whitespace().collect(),
+    };
+    elements
+        .into_iter()
+        .map(|element| {
+            let offset = element.as_ptr() as usize - text.as_ptr() as usize;
+            parse(name, position + offset, element)
+        })
+        .collect()
+}
+
+/// Fails if there are any tokens left.
+pub fn end(tokens: &mut Tokenizer) -> Result<(), ScanError> {
+    match tokens.next() {
+        Some(token) => {
+            let message = format!(r#"unexpected "{}" at end of input"#, token.text);
+            Err(ScanError::new(token.span.start, message))
+        }
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_terminator() {
+        assert_eq!(Terminator::before(" = ("), Terminator::Literal(" = ("));
+        assert_eq!(Terminator::before(")"), Terminator::Literal(")"));
+        assert_eq!(Terminator::before("  "), Terminator::Whitespace);
+    }
+
+    #[test]
+    fn test_field() {
+        let input = "AAA = (BBB, -12)";
+        let mut tokens = Tokenizer::new(input);
+        let name = field(&mut tokens, input, "name", Terminator::before(" = ("));
+        assert_eq!(name, Ok((0, "AAA")));
+        literal(&mut tokens, input, " = (").unwrap();
+        let left = field(&mut tokens, input, "left", Terminator::before(","));
+        assert_eq!(left, Ok((7, "BBB")));
+        literal(&mut tokens, input, ",").unwrap();
+        let right = field(&mut tokens, input, "right", Terminator::before(")"));
+        assert_eq!(right, Ok((12, "-12")));
+        literal(&mut tokens, input, ")").unwrap();
+        assert_eq!(end(&mut tokens), Ok(()));
+    }
+
+    #[test]
+    fn test_literal_terminator() {
+        // A field can start with the literal's first token
+        let input = "-5 -> -3";
+        let mut tokens = Tokenizer::new(input);
+        let from = field(&mut tokens, input, "from", Terminator::before(" -> "));
+        assert_eq!(from, Ok((0, "-5")));
+        literal(&mut tokens, input, " -> ").unwrap();
+
+        let input = "-5 - > 3";
+        let mut tokens = Tokenizer::new(input);
+        let from = field(&mut tokens, input, "from", Terminator::before(" -> "));
+        assert_eq!(from, Ok((0, "-5")));
+
+        let input = "-5 -< 3";
+        let mut tokens = Tokenizer::new(input);
+        let from = field(&mut tokens, input, "from", Terminator::before(" -> "));
+        assert_eq!(
+            from,
+            Err(ScanError::new(0, r#"expected "->" after `from`"#))
+        );
+    }
+
+    #[test]
+    fn test_whitespace_terminator() {
+        let input = "-3 seed-to-soil  x";
+        let mut tokens = Tokenizer::new(input);
+        let first = field(&mut tokens, input, "a", Terminator::Whitespace);
+        assert_eq!(first, Ok((0, "-3")));
+        let second = field(&mut tokens, input, "b", Terminator::Whitespace);
+        assert_eq!(second, Ok((3, "seed-to-soil")));
+        let third = field(&mut tokens, input, "c", Terminator::End);
+        assert_eq!(third, Ok((17, "x")));
+    }
+
+    #[test]
+    fn test_parse_list() {
+        let list: Result<Vec<u32>, ScanError> = parse_list("list", 10, "1, 2,3", Some(","));
+        assert_eq!(list, Ok(vec![1, 2, 3]));
+
+        let list: Result<Vec<u32>, ScanError> = parse_list("list", 10, " 1  2 3", None);
+        assert_eq!(list, Ok(vec![1, 2, 3]));
+
+        let list: Result<Vec<u32>, ScanError> = parse_list("list", 10, "1, x", Some(","));
+        assert_eq!(list.unwrap_err().position, 13);
+    }
+}
@@ -0,0 +1,122 @@
+use std::env;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader};
+
+/// Reads lines from a `BufRead` one at a time into a single reused buffer,
+/// so memory use depends on the longest line rather than the size of the
+/// input. Strings and byte slices are `BufRead` too, so the same code can
+/// process `include_str!` input via `input.as_bytes()`.
+pub struct LineReader<R> {
+    reader: R,
+    buffer: String,
+}
+
+impl<R: BufRead> LineReader<R> {
+    pub fn new(reader: R) -> Self {
+        LineReader {
+            reader,
+            buffer: String::new(),
+        }
+    }
+
+    /// Returns the next line without its line ending, or `None` at the end
+    /// of the input. Like `str::lines`, both `\n` and `\r\n` are understood.
+    pub fn next_line(&mut self) -> Option<io::Result<&str>> {
+        self.buffer.clear();
+        match self.reader.read_line(&mut self.buffer) {
+            Ok(0) => None,
+            Ok(_) => {
+                let line = self.buffer.strip_suffix('\n').unwrap_or(&self.buffer);
+                Some(Ok(line.strip_suffix('\r').unwrap_or(line)))
+            }
+            Err(error) => Some(Err(error)),
+        }
+    }
+
+    /// Calls `f` on each remaining line, stopping at the first read error.
+    pub fn for_each_line(mut self, mut f: impl FnMut(&str)) -> io::Result<()> {
+        while let Some(line) = self.next_line() {
+            f(line?);
+        }
+        Ok(())
+    }
+}
+
+/// Opens the input file named by the first command-line argument for
+/// streaming, where `-` means stdin. Returns `None` if there's no argument,
+/// so the caller can fall back to the input it was built with.
+pub fn input_arg() -> Option<io::Result<Box<dyn BufRead>>> {
+    let path = env::args().nth(1)?;
+    if path == "-" {
+        return Some(Ok(Box::new(io::stdin().lock())));
+    }
+    Some(File::open(path).map(|file| Box::new(BufReader::new(file)) as Box<dyn BufRead>))
+}
+
+/// Runs a solution on the input file named on the command line, streaming
+/// it, or on `input` if there's no argument. This is all a binary's `main`
+/// needs besides printing the answer:
+///
+/// ```no_run
+/// # use std::io::BufRead;
+/// # fn process_reader(reader: impl BufRead) -> String { String::new() }
+/// let output = aoc_utils::run("1\n2\n", process_reader);
+/// ```
+pub fn run(input: &'static str, process: impl FnOnce(Box<dyn BufRead>) -> String) -> String {
+    let reader = match input_arg() {
+        Some(reader) => reader.unwrap_or_else(|error| panic!("can't open input: {error}")),
+        None => Box::new(input.as_bytes()),
+    };
+    process(reader)
+}
+
+/// Calls `f` on each line of `reader`, panicking if it can't be read.
+pub fn each_line(reader: impl BufRead, f: impl FnMut(&str)) {
+    LineReader::new(reader)
+        .for_each_line(f)
+        .unwrap_or_else(|error| panic!("can't read input: {error}"));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_next_line() {
+        let mut lines = LineReader::new("a\nbc\r\n\nd".as_bytes());
+        assert_eq!(lines.next_line().unwrap().unwrap(), "a");
+        assert_eq!(lines.next_line().unwrap().unwrap(), "bc");
+        assert_eq!(lines.next_line().unwrap().unwrap(), "");
+        assert_eq!(lines.next_line().unwrap().unwrap(), "d");
+        assert!(lines.next_line().is_none());
+        assert!(lines.next_line().is_none());
+    }
+
+    #[test]
+    fn test_matches_str_lines() {
+        let input = "Game 1: 3 blue\r\n  Game 2: 4 red\n\n";
+        let mut found = Vec::new();
+        LineReader::new(input.as_bytes())
+            .for_each_line(|line| found.push(line.to_string()))
+            .unwrap();
+        assert_eq!(found, input.lines().collect::<Vec<&str>>());
+    }
+
+    #[test]
+    fn test_each_line() {
+        let mut total = 0;
+        each_line("1\n22\r\n333".as_bytes(), |line| total += line.len());
+        assert_eq!(total, 6);
+    }
+
+    #[test]
+    fn test_small_buffer() {
+        // Lines longer than the reader's buffer still come back whole
+        let input = format!("{}\n{}", "x".repeat(100), "y".repeat(50));
+        let reader = BufReader::with_capacity(8, input.as_bytes());
+        let mut lines = LineReader::new(reader);
+        assert_eq!(lines.next_line().unwrap().unwrap().len(), 100);
+        assert_eq!(lines.next_line().unwrap().unwrap().len(), 50);
+        assert!(lines.next_line().is_none());
+    }
+}
@@ -0,0 +1,88 @@
+use std::collections::HashMap;
+
+/// Maps names to dense ids, starting at 0, in the order they're first seen.
+/// This lets node-based puzzles store their graphs in plain vectors indexed
+/// by id instead of hashing strings at every step.
+#[derive(Clone, Debug, Default)]
+pub struct Interner<'a> {
+    ids: HashMap<&'a str, u32>,
+    names: Vec<&'a str>,
+}
+
+impl<'a> Interner<'a> {
+    pub fn new() -> Self {
+        Interner {
+            ids: HashMap::new(),
+            names: Vec::new(),
+        }
+    }
+
+    /// Returns the id for `name`, assigning the next free id if it hasn't
+    /// been seen before.
+    pub fn intern(&mut self, name: &'a str) -> u32 {
+        if let Some(&id) = self.ids.get(name) {
+            return id;
+        }
+        let id = self.names.len() as u32;
+        self.ids.insert(name, id);
+        self.names.push(name);
+        id
+    }
+
+    /// Returns the id for `name`, if it has been interned.
+    pub fn get(&self, name: &str) -> Option<u32> {
+        self.ids.get(name).copied()
+    }
+
+    /// Returns the name for `id`. Panics if `id` wasn't handed out by this
+    /// interner.
+    pub fn name(&self, id: u32) -> &'a str {
+        self.names[id as usize]
+    }
+
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    /// Iterates over the ids and their names in id order.
+    pub fn iter(&self) -> impl Iterator<Item = (u32, &'a str)> + '_ {
+        self.names
+            .iter()
+            .enumerate()
+            .map(|(id, name)| (id as u32, *name))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_intern() {
+        let mut names = Interner::new();
+        assert!(names.is_empty());
+        assert_eq!(names.intern("AAA"), 0);
+        assert_eq!(names.intern("BBB"), 1);
+        assert_eq!(names.intern("AAA"), 0);
+        assert_eq!(names.intern("CCC"), 2);
+        assert_eq!(names.len(), 3);
+    }
+
+    #[test]
+    fn test_lookup() {
+        let mut names = Interner::new();
+        names.intern("AAA");
+        names.intern("BBB");
+        assert_eq!(names.get("BBB"), Some(1));
+        assert_eq!(names.get("ZZZ"), None);
+        assert_eq!(names.name(0), "AAA");
+        assert_eq!(names.name(1), "BBB");
+
+        let all: Vec<(u32, &str)> = names.iter().collect();
+        assert_eq!(all, vec![(0, "AAA"), (1, "BBB")]);
+    }
+}
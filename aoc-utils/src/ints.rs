@@ -0,0 +1,164 @@
+use std::marker::PhantomData;
+
+/// An integer type that `ints` can extract from text.
+pub trait Int: Copy {
+    /// Whether a `-` in front of a number makes it negative
+    const SIGNED: bool;
+
+    /// Converts a run of ASCII digits, returning `None` if the value doesn't
+    /// fit.
+    fn from_digits(digits: &[u8], negative: bool) -> Option<Self>;
+}
+
+macro_rules! impl_int {
+    ($signed:expr; $($type:ty),+) => {
+        $(
+            impl Int for $type {
+                const SIGNED: bool = $signed;
+
+                fn from_digits(digits: &[u8], negative: bool) -> Option<Self> {
+                    // Numbers shorter than MAX can't overflow, so only long
+                    // ones need the checked arithmetic. Negative values are
+                    // built downwards so that MIN fits.
+                    let mut value: Self = 0;
+                    if digits.len() <= Self::MAX.ilog10() as usize {
+                        for digit in digits {
+                            value = value * 10 + (digit - b'0') as Self;
+                        }
+                        return Some(if negative { 0 - value } else { value });
+                    }
+                    for digit in digits {
+                        let digit = (digit - b'0') as Self;
+                        value = value.checked_mul(10)?;
+                        value = if negative {
+                            value.checked_sub(digit)?
+                        } else {
+                            value.checked_add(digit)?
+                        };
+                    }
+                    Some(value)
+                }
+            }
+        )+
+    };
+}
+
+impl_int!(true; i8, i16, i32, i64, i128, isize);
+impl_int!(false; u8, u16, u32, u64, u128, usize);
+
+/// Returns every integer in `text`, in order, ignoring anything else, so
+/// `ints::<u32>("Card  3:  1 21 | 69")` gives `[3, 1, 21, 69]`.
+///
+/// For signed types, a `-` directly in front of a number makes it negative,
+/// unless the `-` follows a letter or digit, as in `x-1` or `10-12`. Unsigned
+/// types ignore `-`.
+///
+/// Panics if a number doesn't fit in `T`.
+pub fn ints<T: Int>(text: &str) -> Vec<T> {
+    ints_iter(text).collect()
+}
+
+/// Like `ints`, but returns an iterator instead of collecting the numbers.
+pub fn ints_iter<T: Int>(text: &str) -> Ints<'_, T> {
+    Ints {
+        text,
+        position: 0,
+        phantom: PhantomData,
+    }
+}
+
+pub struct Ints<'a, T> {
+    text: &'a str,
+    position: usize,
+    phantom: PhantomData<T>,
+}
+
+impl<T: Int> Iterator for Ints<'_, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // Digits and '-' are ASCII, so working on bytes can't split a char
+        let bytes = self.text.as_bytes();
+        let mut start = self.position;
+        while start < bytes.len() && !bytes[start].is_ascii_digit() {
+            start += 1;
+        }
+        if start == bytes.len() {
+            self.position = start;
+            return None;
+        }
+        let mut end = start + 1;
+        while end < bytes.len() && bytes[end].is_ascii_digit() {
+            end += 1;
+        }
+        self.position = end;
+
+        let negative = T::SIGNED
+            && start > 0
+            && bytes[start - 1] == b'-'
+            && (start == 1 || !bytes[start - 2].is_ascii_alphanumeric());
+        match T::from_digits(&bytes[start..end], negative) {
+            Some(value) => Some(value),
+            None => {
+                let start = if negative { start - 1 } else { start };
+                let number = &self.text[start..end];
+                panic!("{number} doesn't fit in {}", std::any::type_name::<T>())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ints() {
+        let found: Vec<u32> = ints("Card  3:  1 21 53 | 69 82");
+        assert_eq!(found, vec![3, 1, 21, 53, 69, 82]);
+        let found: Vec<u64> = ints("Time:      7  15   30\n");
+        assert_eq!(found, vec![7, 15, 30]);
+        let found: Vec<u8> = ints("no numbers here");
+        assert_eq!(found, vec![]);
+    }
+
+    #[test]
+    fn test_signs() {
+        let found: Vec<i64> = ints("x=-3, y=12 -7 a-1 10-12 --5");
+        assert_eq!(found, vec![-3, 12, -7, 1, 10, 12, -5]);
+        let found: Vec<u64> = ints("x=-3, y=12");
+        assert_eq!(found, vec![3, 12]);
+    }
+
+    #[test]
+    fn test_limits() {
+        let found: Vec<i8> = ints("-128 127 0");
+        assert_eq!(found, vec![i8::MIN, i8::MAX, 0]);
+        let found: Vec<u64> = ints("18446744073709551615");
+        assert_eq!(found, vec![u64::MAX]);
+        let found: Vec<u32> = ints("007");
+        assert_eq!(found, vec![7]);
+    }
+
+    #[test]
+    fn test_non_ascii() {
+        let found: Vec<i32> = ints("€5 → −3 ½ 42é");
+        assert_eq!(found, vec![5, 3, 42]);
+    }
+
+    #[test]
+    fn test_iter() {
+        let mut numbers = ints_iter::<u32>("1 2 3");
+        assert_eq!(numbers.next(), Some(1));
+        assert_eq!(numbers.next(), Some(2));
+        assert_eq!(numbers.next(), Some(3));
+        assert_eq!(numbers.next(), None);
+        assert_eq!(numbers.next(), None);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_overflow() {
+        let _: Vec<u8> = ints("256");
+    }
+}
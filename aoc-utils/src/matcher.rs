@@ -0,0 +1,159 @@
+use std::collections::VecDeque;
+
+/// A match of one of the patterns in a `Matcher`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Match {
+    /// The index of the pattern that matched, in the order the patterns were
+    /// given to `Matcher::new`
+    pub pattern: usize,
+    /// Byte offset of the start of the match
+    pub start: usize,
+    /// Byte offset just past the end of the match
+    pub end: usize,
+}
+
+/// Finds every occurrence of a set of patterns in a single pass, including
+/// occurrences that overlap each other, like "one" and "eight" in
+/// "oneight". This is the Aho-Corasick algorithm.
+pub struct Matcher {
+    // For each state, the state to move to on each possible byte
+    transitions: Vec<[u32; 256]>,
+    // For each state, the patterns that end there
+    outputs: Vec<Vec<usize>>,
+    lengths: Vec<usize>,
+}
+
+impl Matcher {
+    pub fn new<I, P>(patterns: I) -> Self
+    where
+        I: IntoIterator<Item = P>,
+        P: AsRef<[u8]>,
+    {
+        // Build a trie of the patterns. State 0 is the root, so no other
+        // state can have a transition to 0, which lets 0 mean "no transition"
+        // while the trie is being built.
+        let mut transitions: Vec<[u32; 256]> = vec![[0; 256]];
+        let mut outputs: Vec<Vec<usize>> = vec![Vec::new()];
+        let mut lengths: Vec<usize> = Vec::new();
+        for (index, pattern) in patterns.into_iter().enumerate() {
+            let pattern = pattern.as_ref();
+            assert!(!pattern.is_empty(), "patterns can't be empty");
+            let mut state = 0;
+            for &byte in pattern {
+                if transitions[state][byte as usize] == 0 {
+                    transitions[state][byte as usize] = transitions.len() as u32;
+                    transitions.push([0; 256]);
+                    outputs.push(Vec::new());
+                }
+                state = transitions[state][byte as usize] as usize;
+            }
+            outputs[state].push(index);
+            lengths.push(pattern.len());
+        }
+
+        // Walk the trie breadth first, working out where to go when a byte
+        // doesn't extend the current match. That's wherever the longest
+        // proper suffix of the current match (its failure state) would go,
+        // which has already been worked out since it's closer to the root.
+        let mut failures: Vec<u32> = vec![0; transitions.len()];
+        let mut queue: VecDeque<usize> = VecDeque::new();
+        for &child in transitions[0].iter() {
+            if child != 0 {
+                queue.push_back(child as usize);
+            }
+        }
+        while let Some(state) = queue.pop_front() {
+            let failure = failures[state] as usize;
+            let inherited = outputs[failure].clone();
+            outputs[state].extend(inherited);
+            let fallbacks = transitions[failure];
+            for (child, &fallback) in transitions[state].iter_mut().zip(fallbacks.iter()) {
+                if *child == 0 {
+                    *child = fallback;
+                } else {
+                    failures[*child as usize] = fallback;
+                    queue.push_back(*child as usize);
+                }
+            }
+        }
+
+        Matcher {
+            transitions,
+            outputs,
+            lengths,
+        }
+    }
+
+    /// Returns every match in `haystack`, ordered by where they end. Matches
+    /// that end at the same place are ordered longest first.
+    pub fn find_overlapping<'a>(&'a self, haystack: &'a str) -> impl Iterator<Item = Match> + 'a {
+        let mut state = 0;
+        haystack.bytes().enumerate().flat_map(move |(index, byte)| {
+            state = self.transitions[state][byte as usize] as usize;
+            self.outputs[state].iter().map(move |&pattern| Match {
+                pattern,
+                start: index + 1 - self.lengths[pattern],
+                end: index + 1,
+            })
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cmp::Reverse;
+
+    fn matches(matcher: &Matcher, haystack: &str) -> Vec<(usize, usize)> {
+        matcher
+            .find_overlapping(haystack)
+            .map(|m| (m.pattern, m.start))
+            .collect()
+    }
+
+    #[test]
+    fn test_no_matches() {
+        let matcher = Matcher::new(["one", "two"]);
+        assert_eq!(matches(&matcher, ""), vec![]);
+        assert_eq!(matches(&matcher, "ontwx"), vec![]);
+    }
+
+    #[test]
+    fn test_overlapping() {
+        let matcher = Matcher::new(["one", "two", "eight"]);
+        assert_eq!(matches(&matcher, "eightwone"), vec![(2, 0), (1, 4), (0, 6)]);
+        assert_eq!(matches(&matcher, "oneone"), vec![(0, 0), (0, 3)]);
+    }
+
+    #[test]
+    fn test_nested_patterns() {
+        let matcher = Matcher::new(["he", "she", "his", "hers"]);
+        let found: Vec<Match> = matcher.find_overlapping("ushers").collect();
+        let found: Vec<(usize, usize, usize)> =
+            found.iter().map(|m| (m.pattern, m.start, m.end)).collect();
+        assert_eq!(found, vec![(1, 1, 4), (0, 2, 4), (3, 2, 6)]);
+    }
+
+    #[test]
+    fn test_brute_force() {
+        let patterns = ["a", "ab", "bab", "bc", "bca", "c", "caa"];
+        let matcher = Matcher::new(patterns);
+        let haystack = "abccabbcaabcabcbacabbcaaca";
+
+        let mut expected: Vec<(usize, usize, usize)> = Vec::new();
+        for end in 1..=haystack.len() {
+            for (pattern, text) in patterns.iter().enumerate() {
+                if haystack[..end].ends_with(text) {
+                    expected.push((end, pattern, end - text.len()));
+                }
+            }
+        }
+        expected.sort_by_key(|&(end, pattern, _)| (end, Reverse(patterns[pattern].len())));
+
+        let actual: Vec<(usize, usize, usize)> = matcher
+            .find_overlapping(haystack)
+            .map(|m| (m.end, m.pattern, m.start))
+            .collect();
+        assert_eq!(actual, expected);
+    }
+}
@@ -0,0 +1,161 @@
+use std::ops::RangeInclusive;
+
+/// Returns the largest integer whose square is no greater than `n`.
+pub fn isqrt(n: u128) -> u128 {
+    if n < 2 {
+        return n;
+    }
+
+    // Start from the floating point estimate, which is within a few units of
+    // the answer, then correct it using exact integer arithmetic.
+    let mut root = (n as f64).sqrt() as u128;
+    while root.checked_mul(root).is_none_or(|square| square > n) {
+        root -= 1;
+    }
+    while (root + 1)
+        .checked_mul(root + 1)
+        .is_some_and(|square| square <= n)
+    {
+        root += 1;
+    }
+    root
+}
+
+/// Returns the range of integers `x` in `0..=time` for which
+/// `x * (time - x) > distance`, or `None` if there are no such integers.
+///
+/// This is the race from day 6: holding the button for `x` milliseconds gives
+/// a speed of `x`, leaving `time - x` milliseconds to travel.
+pub fn beats_distance(
+    time: impl Into<u128>,
+    distance: impl Into<u128>,
+) -> Option<RangeInclusive<u128>> {
+    let time = time.into();
+    let distance = distance.into();
+
+    // The product is symmetric around time / 2, where it peaks
+    let peak = time / 2;
+    if !exceeds(peak, time, distance) {
+        return None;
+    }
+
+    // The smallest root of x^2 - time * x + distance = 0 is
+    // (time - sqrt(time^2 - 4 * distance)) / 2. When the discriminant fits in
+    // a u128 this gets us within one of the answer, otherwise bisect.
+    let discriminant = time
+        .checked_mul(time)
+        .zip(distance.checked_mul(4))
+        .map(|(square, four_distance)| square - four_distance);
+    let mut lower = match discriminant {
+        Some(discriminant) => (time - isqrt(discriminant)) / 2,
+        None => {
+            let (mut low, mut high) = (0, peak);
+            while low < high {
+                let mid = low + (high - low) / 2;
+                if exceeds(mid, time, distance) {
+                    high = mid;
+                } else {
+                    low = mid + 1;
+                }
+            }
+            low
+        }
+    };
+    while !exceeds(lower, time, distance) {
+        lower += 1;
+    }
+    while lower > 0 && exceeds(lower - 1, time, distance) {
+        lower -= 1;
+    }
+
+    Some(lower..=time - lower)
+}
+
+#[inline(always)]
+fn exceeds(x: u128, time: u128, distance: u128) -> bool {
+    x.checked_mul(time - x)
+        .is_none_or(|product| product > distance)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_isqrt() {
+        assert_eq!(isqrt(0), 0);
+        assert_eq!(isqrt(1), 1);
+        assert_eq!(isqrt(3), 1);
+        assert_eq!(isqrt(4), 2);
+        assert_eq!(isqrt(99), 9);
+        assert_eq!(isqrt(100), 10);
+        assert_eq!(isqrt(u128::MAX), u64::MAX as u128);
+        assert_eq!(isqrt((1 << 53) + 1), 94906265);
+    }
+
+    #[test]
+    fn test_isqrt_brute_force() {
+        for n in 0..10_000_u128 {
+            let root = isqrt(n);
+            assert!(root * root <= n, "isqrt({n}) = {root}");
+            assert!((root + 1) * (root + 1) > n, "isqrt({n}) = {root}");
+        }
+    }
+
+    #[test]
+    fn test_isqrt_near_squares() {
+        for root in [1_u128 << 26, 1 << 40, (1 << 53) - 1, 1 << 60, 1 << 63] {
+            let square = root * root;
+            assert_eq!(isqrt(square - 1), root - 1);
+            assert_eq!(isqrt(square), root);
+            assert_eq!(isqrt(square + 1), root);
+        }
+    }
+
+    #[test]
+    fn test_beats_distance() {
+        assert_eq!(beats_distance(7_u64, 9_u64), Some(2..=5));
+        assert_eq!(beats_distance(15_u64, 40_u64), Some(4..=11));
+        assert_eq!(beats_distance(30_u64, 200_u64), Some(11..=19));
+        assert_eq!(
+            beats_distance(71530_u64, 940200_u64).map(|r| r.count()),
+            Some(71503)
+        );
+        assert_eq!(beats_distance(4_u64, 4_u64), None);
+        assert_eq!(beats_distance(0_u64, 0_u64), None);
+    }
+
+    #[test]
+    fn test_beats_distance_brute_force() {
+        for time in 0..60_u128 {
+            for distance in 0..(time * time / 4 + 3) {
+                let expected: Vec<u128> =
+                    (0..=time).filter(|x| x * (time - x) > distance).collect();
+                let actual: Vec<u128> = beats_distance(time, distance)
+                    .map(|range| range.collect())
+                    .unwrap_or_default();
+                assert_eq!(actual, expected, "time: {time}, distance: {distance}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_beats_distance_large() {
+        // Beyond 2^53 an f64 can't represent every integer
+        let time: u64 = (1 << 60) + 1;
+        let range = beats_distance(time, 0_u64).unwrap();
+        assert_eq!(range, 1..=(time as u128 - 1));
+
+        let range = beats_distance(time, time - 2).unwrap();
+        assert_eq!(range, 1..=(time as u128 - 1));
+        let range = beats_distance(time, time - 1).unwrap();
+        assert_eq!(range, 2..=(time as u128 - 2));
+
+        // Too big for the discriminant to fit in a u128
+        let time = u128::MAX;
+        let range = beats_distance(time, 0_u128).unwrap();
+        assert_eq!(range, 1..=(time - 1));
+        let range = beats_distance(time, time - 1).unwrap();
+        assert_eq!(range, 2..=(time - 2));
+    }
+}
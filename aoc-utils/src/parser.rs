@@ -0,0 +1,390 @@
+//! A small set of parser combinators over `&str`.
+//!
+//! A parser is anything implementing `Parser`, which includes every
+//! `Fn(&str) -> ParseResult<T>`. On success it returns the parsed value and
+//! the rest of the input; on failure it returns a `ParseError` saying where
+//! it failed and what it expected there. Like `Tokenizer`, the parsers that
+//! read text skip any whitespace in front of it. The combinators return
+//! closures, so a parser can be reused by passing a reference to it.
+//!
+//! ```
+//! use aoc_utils::parser::{delimited, int, map, parse_all, sep_by, tag, ident, preceded, pair};
+//!
+//! let cube = pair(int::<u32>(), ident());
+//! let game = pair(
+//!     delimited(tag("Game"), int::<u32>(), tag(":")),
+//!     sep_by(sep_by(cube, tag(",")), tag(";")),
+//! );
+//! let (id, samples) = parse_all("Game 2: 1 blue, 2 green; 3 red", game).unwrap();
+//! assert_eq!(id, 2);
+//! assert_eq!(samples, vec![vec![(1, "blue"), (2, "green")], vec![(3, "red")]]);
+//!
+//! let negative = map(preceded(tag("-"), int::<i32>()), |n| -n);
+//! assert_eq!(parse_all("- 5", negative), Ok(-5));
+//! ```
+
+use crate::{Int, ScanError, Tokenizer};
+
+/// The result of running a parser: the parsed value and the unparsed rest of
+/// the input.
+pub type ParseResult<'a, T> = Result<(T, &'a str), ParseError<'a>>;
+
+/// Why a parser failed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseError<'a> {
+    /// The input remaining where the parser failed
+    pub rest: &'a str,
+    /// What was expected there, e.g. `"red"` or `an integer`
+    pub expected: String,
+}
+
+impl<'a> ParseError<'a> {
+    pub fn new(rest: &'a str, expected: impl Into<String>) -> Self {
+        ParseError {
+            rest,
+            expected: expected.into(),
+        }
+    }
+
+    /// Converts this into a `ScanError` holding its position in `input`,
+    /// which must be the input the failing parser was run on, or a string
+    /// that input was sliced from.
+    pub fn to_scan_error(&self, input: &str) -> ScanError {
+        let position = self.rest.as_ptr() as usize - input.as_ptr() as usize;
+        let found = match self.rest.split_whitespace().next() {
+            Some(word) => format!(r#""{word}""#),
+            None => "end of input".to_string(),
+        };
+        ScanError::new(
+            position,
+            format!("expected {}, found {found}", self.expected),
+        )
+    }
+}
+
+pub trait Parser<'a, T> {
+    fn parse(&self, input: &'a str) -> ParseResult<'a, T>;
+}
+
+impl<'a, T, F> Parser<'a, T> for F
+where
+    F: Fn(&'a str) -> ParseResult<'a, T>,
+{
+    fn parse(&self, input: &'a str) -> ParseResult<'a, T> {
+        self(input)
+    }
+}
+
+/// Runs `parser` on the whole of `input`, failing if anything but
+/// whitespace is left over.
+pub fn parse_all<'a, T>(input: &'a str, parser: impl Parser<'a, T>) -> Result<T, ScanError> {
+    let (value, rest) = parser
+        .parse(input)
+        .map_err(|error| error.to_scan_error(input))?;
+    let rest = rest.trim_start();
+    if !rest.is_empty() {
+        return Err(ParseError::new(rest, "end of input").to_scan_error(input));
+    }
+    Ok(value)
+}
+
+/// Matches the text `tag`, returning it.
+pub fn tag<'a>(tag: &'a str) -> impl Fn(&'a str) -> ParseResult<'a, &'a str> {
+    move |input: &'a str| {
+        let rest = input.trim_start();
+        match rest.strip_prefix(tag) {
+            Some(after) => Ok((&rest[..tag.len()], after)),
+            None => Err(ParseError::new(rest, format!(r#""{tag}""#))),
+        }
+    }
+}
+
+/// Parses a run of ASCII digits, with a leading `-` if `T` is signed.
+pub fn int<'a, T: Int>() -> impl Fn(&'a str) -> ParseResult<'a, T> {
+    move |input: &'a str| {
+        let rest = input.trim_start();
+        let negative = T::SIGNED && rest.starts_with('-');
+        let digits_start = usize::from(negative);
+        let length = rest[digits_start..]
+            .bytes()
+            .take_while(u8::is_ascii_digit)
+            .count();
+        if length == 0 {
+            return Err(ParseError::new(rest, "an integer"));
+        }
+        let end = digits_start + length;
+        match T::from_digits(&rest.as_bytes()[digits_start..end], negative) {
+            Some(value) => Ok((value, &rest[end..])),
+            None => {
+                let expected = format!("an integer that fits in {}", std::any::type_name::<T>());
+                Err(ParseError::new(rest, expected))
+            }
+        }
+    }
+}
+
+/// Parses an identifier: a run of alphanumeric characters or `_`.
+pub fn ident<'a>() -> impl Fn(&'a str) -> ParseResult<'a, &'a str> {
+    move |input: &'a str| {
+        let rest = input.trim_start();
+        let length = rest
+            .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+            .unwrap_or(rest.len());
+        if length == 0 {
+            return Err(ParseError::new(rest, "an identifier"));
+        }
+        Ok((&rest[..length], &rest[length..]))
+    }
+}
+
+/// Transforms the value parsed by `parser` with `f`.
+pub fn map<'a, A, B>(
+    parser: impl Parser<'a, A>,
+    f: impl Fn(A) -> B,
+) -> impl Fn(&'a str) -> ParseResult<'a, B> {
+    move |input: &'a str| {
+        let (value, rest) = parser.parse(input)?;
+        Ok((f(value), rest))
+    }
+}
+
+/// Runs `first` then `second`, returning both values.
+pub fn pair<'a, A, B>(
+    first: impl Parser<'a, A>,
+    second: impl Parser<'a, B>,
+) -> impl Fn(&'a str) -> ParseResult<'a, (A, B)> {
+    move |input: &'a str| {
+        let (a, rest) = first.parse(input)?;
+        let (b, rest) = second.parse(rest)?;
+        Ok(((a, b), rest))
+    }
+}
+
+/// Runs `prefix` then `parser`, keeping only the value from `parser`.
+pub fn preceded<'a, P, T>(
+    prefix: impl Parser<'a, P>,
+    parser: impl Parser<'a, T>,
+) -> impl Fn(&'a str) -> ParseResult<'a, T> {
+    map(pair(prefix, parser), |(_, value)| value)
+}
+
+/// Runs `parser` then `suffix`, keeping only the value from `parser`.
+pub fn terminated<'a, T, S>(
+    parser: impl Parser<'a, T>,
+    suffix: impl Parser<'a, S>,
+) -> impl Fn(&'a str) -> ParseResult<'a, T> {
+    map(pair(parser, suffix), |(value, _)| value)
+}
+
+/// Runs `open`, `parser` and `close`, keeping only the value from `parser`.
+pub fn delimited<'a, O, T, C>(
+    open: impl Parser<'a, O>,
+    parser: impl Parser<'a, T>,
+    close: impl Parser<'a, C>,
+) -> impl Fn(&'a str) -> ParseResult<'a, T> {
+    preceded(open, terminated(parser, close))
+}
+
+/// Runs `parser` if it matches, returning `None` if it doesn't.
+pub fn opt<'a, T>(parser: impl Parser<'a, T>) -> impl Fn(&'a str) -> ParseResult<'a, Option<T>> {
+    move |input: &'a str| match parser.parse(input) {
+        Ok((value, rest)) => Ok((Some(value), rest)),
+        Err(_) => Ok((None, input)),
+    }
+}
+
+/// Parses zero or more `item`s separated by `separator`. A separator must be
+/// followed by another item.
+pub fn sep_by<'a, T, S>(
+    item: impl Parser<'a, T>,
+    separator: impl Parser<'a, S>,
+) -> impl Fn(&'a str) -> ParseResult<'a, Vec<T>> {
+    move |input: &'a str| {
+        let mut values = Vec::new();
+        let Ok((value, mut rest)) = item.parse(input) else {
+            return Ok((values, input));
+        };
+        values.push(value);
+        while let Ok((_, after)) = separator.parse(rest) {
+            let (value, after) = item.parse(after)?;
+            values.push(value);
+            rest = after;
+        }
+        Ok((values, rest))
+    }
+}
+
+/// A tuple of parsers to try in turn, for `alt`.
+pub trait Alt<'a, T> {
+    fn choice(&self, input: &'a str) -> ParseResult<'a, T>;
+}
+
+macro_rules! impl_alt {
+    ($($parser:ident),+) => {
+        #[allow(non_snake_case)]
+        impl<'a, T, $($parser: Parser<'a, T>),+> Alt<'a, T> for ($($parser,)+) {
+            fn choice(&self, input: &'a str) -> ParseResult<'a, T> {
+                let ($($parser,)+) = self;
+                let mut error: Option<ParseError<'a>> = None;
+                $(
+                    match $parser.parse(input) {
+                        Ok(result) => return Ok(result),
+                        Err(next) => error = Some(merge(error, next)),
+                    }
+                )+
+                Err(error.unwrap())
+            }
+        }
+    };
+}
+
+impl_alt!(A, B);
+impl_alt!(A, B, C);
+impl_alt!(A, B, C, D);
+impl_alt!(A, B, C, D, E);
+impl_alt!(A, B, C, D, E, F);
+impl_alt!(A, B, C, D, E, F, G);
+impl_alt!(A, B, C, D, E, F, G, H);
+
+// Keeps the error from the parser that got furthest, listing everything
+// that was expected there if several got equally far
+fn merge<'a>(previous: Option<ParseError<'a>>, next: ParseError<'a>) -> ParseError<'a> {
+    match previous {
+        None => next,
+        Some(previous) if previous.rest.len() < next.rest.len() => previous,
+        Some(previous) if previous.rest.len() > next.rest.len() => next,
+        Some(previous) => {
+            let expected = format!("{} or {}", previous.expected, next.expected);
+            ParseError::new(next.rest, expected)
+        }
+    }
+}
+
+/// Tries each parser in the tuple `parsers` in turn, returning the first
+/// that succeeds.
+pub fn alt<'a, T>(parsers: impl Alt<'a, T>) -> impl Fn(&'a str) -> ParseResult<'a, T> {
+    move |input: &'a str| parsers.choice(input)
+}
+
+impl<'a> Tokenizer<'a> {
+    /// Runs `parser` on the input following the tokens read so far, and
+    /// continues tokenizing after whatever it parsed. Error positions are
+    /// byte offsets into the tokenizer's whole input.
+    pub fn parse_with<T>(&mut self, parser: impl Parser<'a, T>) -> Result<T, ScanError> {
+        let input = self.input();
+        let rest = self.rest();
+        let (value, after) = parser
+            .parse(rest)
+            .map_err(|error| error.to_scan_error(input))?;
+        self.skip(rest.len() - after.len());
+        Ok(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tag() {
+        assert_eq!(tag("Game").parse("  Game 1"), Ok(("Game", " 1")));
+        assert_eq!(
+            tag("Game").parse("Gmae 1"),
+            Err(ParseError::new("Gmae 1", r#""Game""#))
+        );
+    }
+
+    #[test]
+    fn test_int() {
+        assert_eq!(int::<u32>().parse(" 42,"), Ok((42, ",")));
+        assert_eq!(int::<i32>().parse("-42"), Ok((-42, "")));
+        assert_eq!(int::<i8>().parse("-128"), Ok((-128, "")));
+        assert!(int::<u32>().parse("-42").is_err());
+        assert!(int::<i32>().parse("- 42").is_err());
+        assert!(int::<u8>().parse("256").is_err());
+        assert!(int::<u8>().parse("x").is_err());
+    }
+
+    #[test]
+    fn test_sep_by() {
+        let numbers = sep_by(int::<u32>(), tag(","));
+        assert_eq!(numbers.parse("1, 2 ,3;"), Ok((vec![1, 2, 3], ";")));
+        assert_eq!(numbers.parse("x"), Ok((vec![], "x")));
+        assert!(numbers.parse("1, 2,").is_err());
+    }
+
+    #[test]
+    fn test_alt() {
+        let color = alt((tag("red"), tag("green"), tag("blue")));
+        assert_eq!(color.parse(" green"), Ok(("green", "")));
+        let error = color.parse(" pink").unwrap_err();
+        assert_eq!(error.expected, r#""red" or "green" or "blue""#);
+
+        // The error comes from the alternative that got furthest
+        let pairs = alt((
+            map(pair(tag("a"), tag("b")), |_| 1),
+            map(pair(tag("a"), tag("c")), |_| 2),
+            map(tag("d"), |_| 3),
+        ));
+        assert_eq!(pairs.parse("ac"), Ok((2, "")));
+        let error = pairs.parse("ax").unwrap_err();
+        assert_eq!(error, ParseError::new("x", r#""b" or "c""#));
+    }
+
+    #[test]
+    fn test_combinators() {
+        let node = pair(
+            terminated(ident(), tag("=")),
+            delimited(
+                tag("("),
+                pair(terminated(ident(), tag(",")), ident()),
+                tag(")"),
+            ),
+        );
+        let (name, (left, right)) = parse_all("AAA = (BBB, CCC)", &node).unwrap();
+        assert_eq!((name, left, right), ("AAA", "BBB", "CCC"));
+
+        let signed = pair(opt(tag("+")), int::<i64>());
+        assert_eq!(parse_all("+5", &signed), Ok((Some("+"), 5)));
+        assert_eq!(parse_all("5", &signed), Ok((None, 5)));
+    }
+
+    #[test]
+    fn test_parse_all_errors() {
+        let game = preceded(tag("Game"), int::<u32>());
+        assert_eq!(
+            parse_all("Game x", &game),
+            Err(ScanError::new(5, r#"expected an integer, found "x""#))
+        );
+        assert_eq!(
+            parse_all("Game 1 2", &game),
+            Err(ScanError::new(7, r#"expected end of input, found "2""#))
+        );
+        assert_eq!(
+            parse_all("Game", &game),
+            Err(ScanError::new(4, "expected an integer, found end of input"))
+        );
+    }
+
+    #[test]
+    fn test_tokenizer() {
+        let input = "move 3 from 1 to 2; 7, 8";
+        let mut tokens = Tokenizer::new(input);
+        tokens.consume("move");
+        let count = tokens.parse_with(int::<u32>()).unwrap();
+        tokens.consume("from");
+        assert_eq!(count, 3);
+        assert_eq!(tokens.get(), "1");
+        tokens.consume("to");
+        tokens.consume("2");
+        tokens.consume(";");
+        let list = tokens.parse_with(sep_by(int::<u32>(), tag(","))).unwrap();
+        assert_eq!(list, vec![7, 8]);
+        assert_eq!(tokens.next(), None);
+
+        let mut tokens = Tokenizer::new(input);
+        tokens.consume("move");
+        let error = tokens.parse_with(tag("4")).unwrap_err();
+        assert_eq!(error.position, 5);
+    }
+}
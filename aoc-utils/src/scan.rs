@@ -0,0 +1,274 @@
+use std::error::Error;
+use std::fmt;
+
+use aoc_utils_pattern::{parse_pattern, Segment};
+
+/// Parses a line against a pattern, returning a tuple of the typed values of
+/// the pattern's fields, or a `ScanError` saying where the line didn't match.
+///
+/// Fields are written as `{name}` in the pattern, and each field needs a
+/// binding of the same name, in the same order, giving its type. A pattern
+/// that's malformed or doesn't match the bindings is an error at position 0.
+/// Whitespace in the pattern matches any amount of whitespace in the line,
+/// including none, whitespace around the line is ignored, and the text of
+/// each field is trimmed before it's converted.
+///
+/// ```
+/// use aoc_utils::scan;
+///
+/// let (id, rest) = scan!("Card   1: 41 48", "Card {id}: {rest}", id: u32, rest: &str).unwrap();
+/// assert_eq!(id, 1);
+/// assert_eq!(rest, "41 48");
+/// ```
+#[macro_export]
+macro_rules! scan {
+    ($input:expr, $pattern:expr, $($name:ident : $type:ty),+ $(,)?) => {
+        'scan: {
+            let fields = match $crate::scan_fields($input, $pattern, &[$(stringify!($name)),+]) {
+                Ok(fields) => fields,
+                Err(error) => break 'scan Err(error),
+            };
+            let mut fields = fields.into_iter();
+            Ok(($(
+                {
+                    let (position, text) = fields.next().unwrap();
+                    match <$type as $crate::FromScan>::from_scan(text) {
+                        Ok(value) => value,
+                        Err(message) => {
+                            let message = format!("bad value for `{}`: {}", stringify!($name), message);
+                            break 'scan Err($crate::ScanError::new(position, message));
+                        }
+                    }
+                },
+            )+))
+        }
+    };
+}
+
+/// The error returned by `scan!` when a line doesn't match its pattern.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ScanError {
+    /// Byte offset into the line where matching failed
+    pub position: usize,
+    pub message: String,
+}
+
+impl ScanError {
+    pub fn new(position: usize, message: impl Into<String>) -> Self {
+        ScanError {
+            position,
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for ScanError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "at position {}: {}", self.position, self.message)
+    }
+}
+
+impl Error for ScanError {}
+
+/// Conversion from the text of a `scan!` field.
+pub trait FromScan<'a>: Sized {
+    fn from_scan(text: &'a str) -> Result<Self, String>;
+}
+
+impl<'a> FromScan<'a> for &'a str {
+    fn from_scan(text: &'a str) -> Result<Self, String> {
+        Ok(text)
+    }
+}
+
+macro_rules! from_scan_via_from_str {
+    ($($type:ty),+) => {
+        $(
+            impl FromScan<'_> for $type {
+                fn from_scan(text: &str) -> Result<Self, String> {
+                    text.parse().map_err(|error| format!(r#""{text}": {error}"#))
+                }
+            }
+        )+
+    };
+}
+
+from_scan_via_from_str!(
+    i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize, f32, f64, bool, char, String
+);
+
+fn skip_whitespace(input: &str, position: usize) -> usize {
+    let rest = &input[position..];
+    position + (rest.len() - rest.trim_start().len())
+}
+
+/// Matches `input` against `pattern` and returns the position and text of
+/// each field. This is the runtime half of `scan!`, which checks that the
+/// fields match the names of its bindings.
+#[doc(hidden)]
+pub fn scan_fields<'a>(
+    input: &'a str,
+    pattern: &str,
+    names: &[&str],
+) -> Result<Vec<(usize, &'a str)>, ScanError> {
+    let segments = parse_pattern(pattern).map_err(|message| ScanError::new(0, message))?;
+    let fields: Vec<&str> = segments
+        .iter()
+        .filter_map(|segment| match segment {
+            Segment::Field(name) => Some(*name),
+            Segment::Literal(_) => None,
+        })
+        .collect();
+    if fields != names {
+        let message =
+            format!(r#"pattern "{pattern}" has fields {fields:?}, but the bindings are {names:?}"#);
+        return Err(ScanError::new(0, message));
+    }
+
+    // Like trailing whitespace, leading whitespace is ignored
+    let mut values = Vec::new();
+    let mut position = skip_whitespace(input, 0);
+    for (index, segment) in segments.iter().enumerate() {
+        match segment {
+            Segment::Literal(literal) => {
+                for c in literal.chars() {
+                    if c.is_whitespace() {
+                        position = skip_whitespace(input, position);
+                    } else if input[position..].starts_with(c) {
+                        position += c.len_utf8();
+                    } else {
+                        return Err(ScanError::new(position, format!("expected '{c}'")));
+                    }
+                }
+            }
+            Segment::Field(name) => {
+                position = skip_whitespace(input, position);
+                let rest = &input[position..];
+
+                // The field runs up to the start of the following literal,
+                // or to the end of the input if it's the last segment
+                let length = match segments.get(index + 1) {
+                    Some(Segment::Literal(literal)) => {
+                        let literal = literal.trim_start();
+                        let terminator = literal.split(char::is_whitespace).next().unwrap();
+                        let found = if terminator.is_empty() {
+                            rest.find(char::is_whitespace)
+                        } else {
+                            rest.find(terminator)
+                        };
+                        match found {
+                            Some(length) => length,
+                            None if terminator.is_empty() => rest.len(),
+                            None => {
+                                let message = format!(r#"expected "{terminator}" after `{name}`"#);
+                                return Err(ScanError::new(position, message));
+                            }
+                        }
+                    }
+                    _ => rest.len(),
+                };
+
+                let text = rest[..length].trim_end();
+                if text.is_empty() {
+                    let message = format!("expected a value for `{name}`");
+                    return Err(ScanError::new(position, message));
+                }
+                values.push((position, text));
+                position += length;
+            }
+        }
+    }
+
+    position = skip_whitespace(input, position);
+    if position != input.len() {
+        return Err(ScanError::new(position, "unexpected text at end of line"));
+    }
+    Ok(values)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ScanError;
+
+    #[test]
+    fn test_scan() {
+        let game = "Game 12: 3 blue, 4 red; 1 red";
+        let (id, rest) = scan!(game, "Game {id}: {rest}", id: usize, rest: &str).unwrap();
+        assert_eq!(id, 12);
+        assert_eq!(rest, "3 blue, 4 red; 1 red");
+    }
+
+    #[test]
+    fn test_extra_spacing() {
+        let card = "  Card   1:  41 48 | 83 86  ";
+        let (id, winning, ours) = scan!(
+            card,
+            "Card {id}: {winning} | {ours}",
+            id: u32,
+            winning: &str,
+            ours: &str
+        )
+        .unwrap();
+        assert_eq!(id, 1);
+        assert_eq!(winning, "41 48");
+        assert_eq!(ours, "83 86");
+    }
+
+    #[test]
+    fn test_whitespace_separated() {
+        let (value, color) = scan!(" 3 blue", "{value} {color}", value: u32, color: &str).unwrap();
+        assert_eq!(value, 3);
+        assert_eq!(color, "blue");
+
+        let (a, b, c) = scan!("-1 2.5 x", "{a} {b} {c}", a: i64, b: f64, c: char).unwrap();
+        assert_eq!((a, b, c), (-1, 2.5, 'x'));
+    }
+
+    #[test]
+    fn test_errors() {
+        let result = scan!("Gmae 1: x", "Game {id}: {rest}", id: u32, rest: &str);
+        assert_eq!(result, Err(ScanError::new(1, "expected 'a'")));
+
+        let result = scan!("Game x: y", "Game {id}: {rest}", id: u32, rest: &str);
+        let error = result.unwrap_err();
+        assert_eq!(error.position, 5);
+        assert!(error.message.starts_with("bad value for `id`"));
+
+        // A missing terminator is reported where the field starts
+        let result = scan!("Game 1 y", "Game {id}: {rest}", id: u32, rest: &str);
+        assert_eq!(
+            result.unwrap_err(),
+            ScanError::new(5, r#"expected ":" after `id`"#)
+        );
+
+        let result = scan!("Game : y", "Game {id}: {rest}", id: u32, rest: &str);
+        assert_eq!(
+            result.unwrap_err(),
+            ScanError::new(5, "expected a value for `id`")
+        );
+
+        let result = scan!("1 2 3", "{a} {b}", a: u32, b: u32);
+        assert_eq!(result.unwrap_err().position, 2);
+    }
+
+    #[test]
+    fn test_bad_patterns() {
+        let result = scan!("1 2", "{a} {b}", a: u32, c: u32);
+        assert_eq!(
+            result.unwrap_err(),
+            ScanError::new(
+                0,
+                r#"pattern "{a} {b}" has fields ["a", "b"], but the bindings are ["a", "c"]"#
+            )
+        );
+
+        let result = scan!("1 2", "{a} {b", a: u32, b: u32);
+        assert_eq!(
+            result.unwrap_err().message,
+            r#"unclosed field in pattern "{a} {b""#
+        );
+
+        let result = scan!("12", "{a}{b}", a: u32, b: u32);
+        assert_eq!(result.unwrap_err().position, 0);
+    }
+}
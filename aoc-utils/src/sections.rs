@@ -0,0 +1,144 @@
+/// Splits `input` into paragraphs: runs of lines separated by one or more
+/// blank lines. Lines holding only whitespace count as blank, and both `\n`
+/// and `\r\n` line endings are understood.
+///
+/// Each paragraph is a slice of `input` from the start of its first line to
+/// the end of its last line, without the final line ending.
+pub fn paragraphs(input: &str) -> Paragraphs<'_> {
+    Paragraphs { input, position: 0 }
+}
+
+/// Splits `input` into paragraphs, and each paragraph into a header and a
+/// body at the first `:` on its first line. The header is trimmed. The body
+/// starts after the `:` and any spaces following it, or on the next line if
+/// nothing follows the `:`, and its lines are left as they are. So
+/// `"seeds: 79 14"` gives `("seeds", "79 14")` and
+/// `"seed-to-soil map:\n50 98 2"` gives `("seed-to-soil map", "50 98 2")`.
+///
+/// A paragraph with no `:` on its first line has an empty header, and all of
+/// it is the body.
+pub fn sections(input: &str) -> impl Iterator<Item = (&str, &str)> {
+    paragraphs(input).map(split_header)
+}
+
+/// Splits a paragraph at the first `:` on its first line, as described for
+/// `sections`.
+pub fn split_header(paragraph: &str) -> (&str, &str) {
+    let first_line = paragraph.lines().next().unwrap_or("");
+    match first_line.find(':') {
+        Some(colon) => {
+            let rest = &paragraph[colon + 1..];
+            let body = match rest.split_once('\n') {
+                Some((line, body)) if line.trim().is_empty() => body,
+                _ => rest.trim_start(),
+            };
+            (paragraph[..colon].trim(), body)
+        }
+        None => ("", paragraph),
+    }
+}
+
+pub struct Paragraphs<'a> {
+    input: &'a str,
+    position: usize,
+}
+
+impl<'a> Paragraphs<'a> {
+    // Returns the line starting at the current position, without its line
+    // ending, and the position of the start of the following line
+    fn line(&self) -> (&'a str, usize) {
+        let rest = &self.input[self.position..];
+        let (line, next) = match rest.find('\n') {
+            Some(end) => (&rest[..end], self.position + end + 1),
+            None => (rest, self.input.len()),
+        };
+        (line.strip_suffix('\r').unwrap_or(line), next)
+    }
+}
+
+impl<'a> Iterator for Paragraphs<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // Skip blank lines
+        let len = self.input.len();
+        while self.position < len {
+            let (line, next) = self.line();
+            if !line.trim().is_empty() {
+                break;
+            }
+            self.position = next;
+        }
+        if self.position == len {
+            return None;
+        }
+
+        let start = self.position;
+        let mut end = start;
+        while self.position < len {
+            let (line, next) = self.line();
+            if line.trim().is_empty() {
+                break;
+            }
+            end = self.position + line.len();
+            self.position = next;
+        }
+        Some(&self.input[start..end])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_paragraphs() {
+        let input = "a\nb\n\nc\n\n\nd\ne\n";
+        let found: Vec<&str> = paragraphs(input).collect();
+        assert_eq!(found, vec!["a\nb", "c", "d\ne"]);
+    }
+
+    #[test]
+    fn test_paragraphs_empty() {
+        assert_eq!(paragraphs("").next(), None);
+        assert_eq!(paragraphs("\n \n\t\n").next(), None);
+    }
+
+    #[test]
+    fn test_paragraphs_whitespace_separators() {
+        let input = "  a\n  b\n    \n  c  ";
+        let found: Vec<&str> = paragraphs(input).collect();
+        assert_eq!(found, vec!["  a\n  b", "  c  "]);
+    }
+
+    #[test]
+    fn test_paragraphs_crlf() {
+        let input = "a\r\nb\r\n\r\nc\r\n";
+        let found: Vec<&str> = paragraphs(input).collect();
+        assert_eq!(found, vec!["a\r\nb", "c"]);
+    }
+
+    #[test]
+    fn test_sections() {
+        let input =
+            "seeds: 79 14 55 13\n\nseed-to-soil map:\n50 98 2\n52 50 48\n\nno header here\n";
+        let found: Vec<(&str, &str)> = sections(input).collect();
+        assert_eq!(
+            found,
+            vec![
+                ("seeds", "79 14 55 13"),
+                ("seed-to-soil map", "50 98 2\n52 50 48"),
+                ("", "no header here"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_split_header_keeps_body_whitespace() {
+        assert_eq!(split_header("map:\n  1 2\n 3  "), ("map", "  1 2\n 3  "));
+        assert_eq!(split_header("map:  \r\n 1 2"), ("map", " 1 2"));
+        assert_eq!(split_header(" seeds :  1  2 "), ("seeds", "1  2 "));
+        assert_eq!(split_header("empty:"), ("empty", ""));
+        assert_eq!(split_header("  no header "), ("", "  no header "));
+    }
+}
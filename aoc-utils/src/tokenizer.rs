@@ -0,0 +1,539 @@
+use std::rc::Rc;
+
+#[derive(Clone, Debug, Default)]
+struct Config {
+    ident_chars: Vec<char>,
+    operators: Vec<String>,
+    line_comment: Option<String>,
+    block_comment: Option<(String, String)>,
+    newlines: bool,
+}
+
+/// Configures which characters make up tokens. The defaults match
+/// `Tokenizer::new`: identifiers are runs of letters and digits, every
+/// other non-whitespace character is a token of its own, there are no
+/// comments, and newlines are just whitespace.
+///
+/// The tokenizers it builds share its configuration rather than copying
+/// it, so one builder can tokenize every line of an input.
+#[derive(Clone, Debug)]
+pub struct TokenizerBuilder {
+    config: Rc<Config>,
+}
+
+impl Default for TokenizerBuilder {
+    fn default() -> Self {
+        thread_local! {
+            static DEFAULT: Rc<Config> = Rc::default();
+        }
+        TokenizerBuilder {
+            config: DEFAULT.with(Rc::clone),
+        }
+    }
+}
+
+impl TokenizerBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn config(&mut self) -> &mut Config {
+        Rc::make_mut(&mut self.config)
+    }
+
+    /// Allows the given characters in identifiers as well as letters and
+    /// digits, e.g. `"-"` so that `seed-to-soil` is a single token.
+    pub fn ident_chars(mut self, chars: &str) -> Self {
+        self.config().ident_chars.extend(chars.chars());
+        self
+    }
+
+    /// Adds a multi-character operator, e.g. `"->"`, to be returned as a
+    /// single token. Operators are matched before identifiers, longest first.
+    /// Panics if `operator` is empty.
+    pub fn operator(mut self, operator: &str) -> Self {
+        assert!(!operator.is_empty(), "operators can't be empty");
+        let operators = &mut self.config().operators;
+        operators.push(operator.to_string());
+        operators.sort_by_key(|op| std::cmp::Reverse(op.len()));
+        self
+    }
+
+    /// Skips everything from `start` to the end of the line. Panics if
+    /// `start` is empty.
+    pub fn line_comment(mut self, start: &str) -> Self {
+        assert!(!start.is_empty(), "comment markers can't be empty");
+        self.config().line_comment = Some(start.to_string());
+        self
+    }
+
+    /// Skips everything from `start` up to and including `end`. Panics if
+    /// either is empty.
+    pub fn block_comment(mut self, start: &str, end: &str) -> Self {
+        assert!(
+            !start.is_empty() && !end.is_empty(),
+            "comment markers can't be empty"
+        );
+        self.config().block_comment = Some((start.to_string(), end.to_string()));
+        self
+    }
+
+    /// When set, each newline is returned as a `"\n"` token instead of being
+    /// skipped as whitespace.
+    pub fn newlines(mut self, significant: bool) -> Self {
+        self.config().newlines = significant;
+        self
+    }
+
+    pub fn build<'a>(&self, input: &'a str) -> Tokenizer<'a> {
+        Tokenizer {
+            input,
+            index: 0,
+            config: Rc::clone(&self.config),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TokenKind {
+    /// A run of identifier characters that isn't all digits
+    Ident,
+    /// A run of ASCII digits
+    Int,
+    /// An operator or any other single character
+    Symbol,
+    /// A newline, only returned when newlines are significant
+    Newline,
+}
+
+/// Byte offsets of a token in the input.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Token<'a> {
+    pub kind: TokenKind,
+    pub text: &'a str,
+    pub span: Span,
+}
+
+/// A position in the input that a `Tokenizer` can be rewound to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Checkpoint(usize);
+
+pub struct Tokenizer<'a> {
+    input: &'a str,
+    index: usize,
+    config: Rc<Config>,
+}
+
+impl<'a> Tokenizer<'a> {
+    pub fn new(input: &'a str) -> Self {
+        TokenizerBuilder::new().build(input)
+    }
+
+    pub fn consume(&mut self, expected: &str) {
+        if let Some(token) = self.next() {
+            if token.text != expected {
+                panic!(r#"expected token: "{expected}", got "{}""#, token.text)
+            }
+        } else {
+            panic!(r#"no more tokens, expected token: "{expected}""#)
+        }
+    }
+
+    #[inline(always)]
+    pub fn get(&mut self) -> &'a str {
+        self.next().unwrap().text
+    }
+
+    /// Returns the next token without consuming it.
+    pub fn peek(&mut self) -> Option<Token<'a>> {
+        self.peek_nth(0)
+    }
+
+    /// Returns the token `n` places after the next one, without consuming
+    /// anything. `peek_nth(0)` is the same as `peek()`.
+    pub fn peek_nth(&mut self, n: usize) -> Option<Token<'a>> {
+        let checkpoint = self.checkpoint();
+        let token = self.nth(n);
+        self.rewind(checkpoint);
+        token
+    }
+
+    /// Saves the current position, so parsing can be retried from here.
+    pub fn checkpoint(&self) -> Checkpoint {
+        Checkpoint(self.index)
+    }
+
+    /// Returns to a position saved by `checkpoint`.
+    pub fn rewind(&mut self, checkpoint: Checkpoint) {
+        self.index = checkpoint.0;
+    }
+
+    /// Returns the input that hasn't been tokenized yet.
+    pub fn rest(&self) -> &'a str {
+        &self.input[self.index..]
+    }
+
+    pub(crate) fn input(&self) -> &'a str {
+        self.input
+    }
+
+    // Moves past `len` bytes of input that were consumed by something else
+    pub(crate) fn skip(&mut self, len: usize) {
+        self.index += len;
+    }
+
+    // The character at the current position, which must be before the end
+    // of the input
+    #[inline(always)]
+    fn char(&self) -> char {
+        self.input[self.index..].chars().next().unwrap()
+    }
+
+    #[inline(always)]
+    fn advance(&mut self) {
+        self.index += self.char().len_utf8();
+    }
+
+    #[inline(always)]
+    fn at(&self, text: &str) -> bool {
+        self.input[self.index..].starts_with(text)
+    }
+
+    #[inline(always)]
+    fn is_ident_char(&self, c: char) -> bool {
+        c.is_alphanumeric() || self.config.ident_chars.contains(&c)
+    }
+
+    #[inline(always)]
+    fn token(&self, kind: TokenKind, start: usize) -> Token<'a> {
+        Token {
+            kind,
+            text: &self.input[start..self.index],
+            span: Span {
+                start,
+                end: self.index,
+            },
+        }
+    }
+
+    // Skips whitespace and comments, stopping at newlines if they're significant
+    fn skip_ignored(&mut self) {
+        let len = self.input.len();
+        loop {
+            while self.index < len
+                && self.char().is_whitespace()
+                && !(self.config.newlines && self.char() == '\n')
+            {
+                self.advance();
+            }
+            if self.index == len {
+                return;
+            }
+
+            if let Some(start) = &self.config.line_comment {
+                if self.at(start) {
+                    while self.index < len && self.char() != '\n' {
+                        self.advance();
+                    }
+                    continue;
+                }
+            }
+            if let Some((start, end)) = &self.config.block_comment {
+                if self.at(start) {
+                    let body = self.index + start.len();
+                    self.index = match self.input[body..].find(end.as_str()) {
+                        Some(offset) => body + offset + end.len(),
+                        None => len,
+                    };
+                    continue;
+                }
+            }
+            return;
+        }
+    }
+}
+
+impl<'a> Iterator for Tokenizer<'a> {
+    type Item = Token<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let len = self.input.len();
+
+        self.skip_ignored();
+        if self.index == len {
+            return None;
+        }
+
+        let start = self.index;
+
+        if self.char() == '\n' {
+            self.advance();
+            return Some(self.token(TokenKind::Newline, start));
+        }
+
+        if let Some(operator) = self.config.operators.iter().find(|op| self.at(op)) {
+            self.index += operator.len();
+            return Some(self.token(TokenKind::Symbol, start));
+        }
+
+        if !self.is_ident_char(self.char()) {
+            self.advance();
+            return Some(self.token(TokenKind::Symbol, start));
+        }
+
+        while self.index < len && self.is_ident_char(self.char()) {
+            self.advance();
+        }
+        let kind = if self.input[start..self.index]
+            .bytes()
+            .all(|c| c.is_ascii_digit())
+        {
+            TokenKind::Int
+        } else {
+            TokenKind::Ident
+        };
+        Some(self.token(kind, start))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_empty_string() {
+        let mut tokens = Tokenizer::new("");
+        assert_eq!(tokens.next().map(|t| t.text), None);
+
+        let mut tokens = Tokenizer::new("    ");
+        assert_eq!(tokens.next().map(|t| t.text), None);
+    }
+
+    #[test]
+    fn test_one_word() {
+        let mut tokens = Tokenizer::new(" abc ");
+        assert_eq!(tokens.next().map(|t| t.text), Some("abc"));
+        assert_eq!(tokens.next().map(|t| t.text), None);
+    }
+
+    #[test]
+    fn test_multiple_words() {
+        let mut tokens = Tokenizer::new(" abc  def  ghi 123");
+        assert_eq!(tokens.next().map(|t| t.text), Some("abc"));
+        assert_eq!(tokens.next().map(|t| t.text), Some("def"));
+        assert_eq!(tokens.next().map(|t| t.text), Some("ghi"));
+        assert_eq!(tokens.next().map(|t| t.text), Some("123"));
+        assert_eq!(tokens.next().map(|t| t.text), None);
+    }
+
+    #[test]
+    fn test_words_and_symbols() {
+        let mut tokens = Tokenizer::new("abc: def = (123, 456)");
+        assert_eq!(tokens.next().map(|t| t.text), Some("abc"));
+        assert_eq!(tokens.next().map(|t| t.text), Some(":"));
+        assert_eq!(tokens.next().map(|t| t.text), Some("def"));
+        assert_eq!(tokens.next().map(|t| t.text), Some("="));
+        assert_eq!(tokens.next().map(|t| t.text), Some("("));
+        assert_eq!(tokens.next().map(|t| t.text), Some("123"));
+        assert_eq!(tokens.next().map(|t| t.text), Some(","));
+        assert_eq!(tokens.next().map(|t| t.text), Some("456"));
+        assert_eq!(tokens.next().map(|t| t.text), Some(")"));
+        assert_eq!(tokens.next().map(|t| t.text), None);
+    }
+
+    #[test]
+    fn test_consume() {
+        let mut tokens = Tokenizer::new("abc: def = (123, 456)");
+        assert_eq!(tokens.next().map(|t| t.text), Some("abc"));
+        tokens.consume(":");
+        assert_eq!(tokens.next().map(|t| t.text), Some("def"));
+        tokens.consume("=");
+        tokens.consume("(");
+        assert_eq!(tokens.next().map(|t| t.text), Some("123"));
+        tokens.consume(",");
+        assert_eq!(tokens.next().map(|t| t.text), Some("456"));
+        tokens.consume(")");
+        assert_eq!(tokens.next().map(|t| t.text), None);
+    }
+
+    #[test]
+    fn test_ident_chars() {
+        let mut tokens = TokenizerBuilder::new()
+            .ident_chars("-_")
+            .build("seed-to-soil map: x_1");
+        assert_eq!(tokens.next().map(|t| t.text), Some("seed-to-soil"));
+        assert_eq!(tokens.next().map(|t| t.text), Some("map"));
+        assert_eq!(tokens.next().map(|t| t.text), Some(":"));
+        assert_eq!(tokens.next().map(|t| t.text), Some("x_1"));
+        assert_eq!(tokens.next().map(|t| t.text), None);
+    }
+
+    #[test]
+    fn test_operators() {
+        let builder = TokenizerBuilder::new()
+            .operator("->")
+            .operator("-")
+            .operator("->>");
+        let tokens: Vec<&str> = builder.build("a -> b->>c - d >").map(|t| t.text).collect();
+        assert_eq!(tokens, vec!["a", "->", "b", "->>", "c", "-", "d", ">"]);
+    }
+
+    #[test]
+    #[should_panic(expected = "operators can't be empty")]
+    fn test_empty_operator() {
+        TokenizerBuilder::new().operator("");
+    }
+
+    #[test]
+    #[should_panic(expected = "comment markers can't be empty")]
+    fn test_empty_line_comment() {
+        TokenizerBuilder::new().line_comment("");
+    }
+
+    #[test]
+    #[should_panic(expected = "comment markers can't be empty")]
+    fn test_empty_block_comment() {
+        TokenizerBuilder::new().block_comment("", "*/");
+    }
+
+    #[test]
+    fn test_comments() {
+        let builder = TokenizerBuilder::new()
+            .line_comment("#")
+            .block_comment("/*", "*/");
+        let input = "a # b c\nd /* e\nf */ g /* h";
+        let tokens: Vec<&str> = builder.build(input).map(|t| t.text).collect();
+        assert_eq!(tokens, vec!["a", "d", "g"]);
+    }
+
+    #[test]
+    fn test_newlines() {
+        let input = "a b\r\n\n  c # d\n";
+        let builder = TokenizerBuilder::new().line_comment("#");
+        let tokens: Vec<&str> = builder.build(input).map(|t| t.text).collect();
+        assert_eq!(tokens, vec!["a", "b", "c"]);
+
+        let builder = builder.newlines(true);
+        let tokens: Vec<&str> = builder.build(input).map(|t| t.text).collect();
+        assert_eq!(tokens, vec!["a", "b", "\n", "\n", "c", "\n"]);
+    }
+
+    #[test]
+    fn test_shared_config() {
+        let builder = TokenizerBuilder::new().operator("->");
+        let first = builder.build("a -> b");
+        let second = builder.build("c -> d");
+        assert!(Rc::ptr_eq(&first.config, &builder.config));
+        assert!(Rc::ptr_eq(&second.config, &builder.config));
+
+        // Changing a builder that's been cloned leaves the clone alone
+        let other = builder.clone().operator("=>");
+        let tokens: Vec<&str> = builder.build("a=>b").map(|t| t.text).collect();
+        assert_eq!(tokens, vec!["a", "=", ">", "b"]);
+        let tokens: Vec<&str> = other.build("a=>b").map(|t| t.text).collect();
+        assert_eq!(tokens, vec!["a", "=>", "b"]);
+    }
+
+    #[test]
+    fn test_tokens_borrow_input() {
+        let input = String::from("abc -> def");
+        let builder = TokenizerBuilder::new().operator("->");
+        let tokens: Vec<&str> = builder.build(&input).map(|t| t.text).collect();
+        drop(builder);
+        assert_eq!(tokens[1].as_ptr(), input[4..].as_ptr());
+    }
+
+    #[test]
+    fn test_kinds_and_spans() {
+        use TokenKind::*;
+
+        let builder = TokenizerBuilder::new().operator("->").newlines(true);
+        let tokens: Vec<(TokenKind, &str, usize, usize)> = builder
+            .build("abc 123 -> 11A\n=")
+            .map(|t| (t.kind, t.text, t.span.start, t.span.end))
+            .collect();
+        assert_eq!(
+            tokens,
+            vec![
+                (Ident, "abc", 0, 3),
+                (Int, "123", 4, 7),
+                (Symbol, "->", 8, 10),
+                (Ident, "11A", 11, 14),
+                (Newline, "\n", 14, 15),
+                (Symbol, "=", 15, 16),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_peek() {
+        let mut tokens = Tokenizer::new("a = 1");
+        assert_eq!(tokens.peek().map(|t| t.text), Some("a"));
+        assert_eq!(tokens.peek().map(|t| t.text), Some("a"));
+        assert_eq!(tokens.peek_nth(1).map(|t| t.text), Some("="));
+        assert_eq!(tokens.peek_nth(2).map(|t| t.kind), Some(TokenKind::Int));
+        assert_eq!(tokens.peek_nth(3), None);
+        assert_eq!(tokens.get(), "a");
+        assert_eq!(tokens.peek().map(|t| t.text), Some("="));
+    }
+
+    #[test]
+    fn test_checkpoint() {
+        let mut tokens = Tokenizer::new("a = (1, 2)");
+        tokens.consume("a");
+        let checkpoint = tokens.checkpoint();
+        tokens.consume("=");
+        tokens.consume("(");
+        assert_eq!(tokens.get(), "1");
+        tokens.rewind(checkpoint);
+        assert_eq!(tokens.get(), "=");
+    }
+
+    #[test]
+    fn test_non_ascii_words() {
+        let mut tokens = Tokenizer::new("café naïve 東京 123");
+        assert_eq!(tokens.next().map(|t| t.text), Some("café"));
+        assert_eq!(tokens.next().map(|t| t.text), Some("naïve"));
+        let token = tokens.next().unwrap();
+        assert_eq!(token.text, "東京");
+        assert_eq!(token.kind, TokenKind::Ident);
+        assert_eq!(token.span, Span { start: 13, end: 19 });
+        assert_eq!(tokens.next().map(|t| t.kind), Some(TokenKind::Int));
+        assert_eq!(tokens.next(), None);
+    }
+
+    #[test]
+    fn test_non_ascii_symbols() {
+        let tokens: Vec<&str> = Tokenizer::new("a→b ≠ c€").map(|t| t.text).collect();
+        assert_eq!(tokens, vec!["a", "→", "b", "≠", "c", "€"]);
+
+        let builder = TokenizerBuilder::new().operator("→→").ident_chars("·");
+        let tokens: Vec<&str> = builder.build("a·b →→ →").map(|t| t.text).collect();
+        assert_eq!(tokens, vec!["a·b", "→→", "→"]);
+    }
+
+    #[test]
+    fn test_unicode_whitespace() {
+        // No-break space, em space and ideographic space
+        let tokens: Vec<&str> = Tokenizer::new("a\u{a0}b\u{2003}c\u{3000}d")
+            .map(|t| t.text)
+            .collect();
+        assert_eq!(tokens, vec!["a", "b", "c", "d"]);
+    }
+
+    #[test]
+    fn test_non_ascii_comments() {
+        let builder = TokenizerBuilder::new()
+            .line_comment("#")
+            .block_comment("«", "»");
+        let input = "a # ünïcödé\nb «é→» c «ü";
+        let tokens: Vec<&str> = builder.build(input).map(|t| t.text).collect();
+        assert_eq!(tokens, vec!["a", "b", "c"]);
+    }
+}
@@ -0,0 +1,298 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// A disjoint set forest over the indices `0..len`, for tracking which items
+/// have been merged into the same component.
+#[derive(Clone, Debug)]
+pub struct UnionFind {
+    parents: Vec<usize>,
+    // Only meaningful for the root of each component
+    sizes: Vec<usize>,
+    component_count: usize,
+}
+
+impl UnionFind {
+    /// Creates `len` components, each containing a single index.
+    pub fn new(len: usize) -> Self {
+        UnionFind {
+            parents: (0..len).collect(),
+            sizes: vec![1; len],
+            component_count: len,
+        }
+    }
+
+    /// Adds a new component containing a single index, and returns the index.
+    pub fn add(&mut self) -> usize {
+        let index = self.parents.len();
+        self.parents.push(index);
+        self.sizes.push(1);
+        self.component_count += 1;
+        index
+    }
+
+    /// Returns the number of indices.
+    pub fn len(&self) -> usize {
+        self.parents.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.parents.is_empty()
+    }
+
+    /// Returns the representative index of the component containing `index`.
+    pub fn find(&mut self, index: usize) -> usize {
+        let mut root = index;
+        while self.parents[root] != root {
+            root = self.parents[root];
+        }
+
+        // Point everything on the path straight at the root
+        let mut current = index;
+        while current != root {
+            let next = self.parents[current];
+            self.parents[current] = root;
+            current = next;
+        }
+        root
+    }
+
+    /// Merges the components containing `a` and `b`. Returns false if they
+    /// were already in the same component.
+    pub fn union(&mut self, a: usize, b: usize) -> bool {
+        let mut a = self.find(a);
+        let mut b = self.find(b);
+        if a == b {
+            return false;
+        }
+
+        // Hang the smaller tree off the larger one to keep paths short
+        if self.sizes[a] < self.sizes[b] {
+            (a, b) = (b, a);
+        }
+        self.parents[b] = a;
+        self.sizes[a] += self.sizes[b];
+        self.component_count -= 1;
+        true
+    }
+
+    pub fn connected(&mut self, a: usize, b: usize) -> bool {
+        self.find(a) == self.find(b)
+    }
+
+    /// Returns the number of indices in the component containing `index`.
+    pub fn size(&mut self, index: usize) -> usize {
+        let root = self.find(index);
+        self.sizes[root]
+    }
+
+    /// Returns the number of components.
+    pub fn component_count(&self) -> usize {
+        self.component_count
+    }
+
+    /// Returns the indices in the component containing `index`, in order.
+    pub fn members(&mut self, index: usize) -> Vec<usize> {
+        let root = self.find(index);
+        (0..self.len()).filter(|&i| self.find(i) == root).collect()
+    }
+
+    /// Returns every component as a list of its indices. Components are
+    /// ordered by their smallest index, and each component's indices are in
+    /// order.
+    pub fn components(&mut self) -> Vec<Vec<usize>> {
+        let mut positions: HashMap<usize, usize> = HashMap::new();
+        let mut components: Vec<Vec<usize>> = Vec::new();
+        for index in 0..self.len() {
+            let root = self.find(index);
+            let position = *positions.entry(root).or_insert_with(|| {
+                components.push(Vec::new());
+                components.len() - 1
+            });
+            components[position].push(index);
+        }
+        components
+    }
+}
+
+/// A disjoint set forest over arbitrary keys. Keys are added the first time
+/// they're seen.
+#[derive(Clone, Debug)]
+pub struct KeyedUnionFind<K> {
+    indexes: HashMap<K, usize>,
+    keys: Vec<K>,
+    sets: UnionFind,
+}
+
+impl<K: Clone + Eq + Hash> KeyedUnionFind<K> {
+    pub fn new() -> Self {
+        KeyedUnionFind {
+            indexes: HashMap::new(),
+            keys: Vec::new(),
+            sets: UnionFind::new(0),
+        }
+    }
+
+    /// Adds `key` in a component of its own, if it hasn't been seen before.
+    /// Returns the key's index.
+    pub fn insert(&mut self, key: K) -> usize {
+        if let Some(&index) = self.indexes.get(&key) {
+            return index;
+        }
+        let index = self.sets.add();
+        self.indexes.insert(key.clone(), index);
+        self.keys.push(key);
+        index
+    }
+
+    /// Returns the number of keys.
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    /// Returns the representative key of the component containing `key`, or
+    /// `None` if the key hasn't been seen.
+    pub fn find(&mut self, key: &K) -> Option<&K> {
+        let index = *self.indexes.get(key)?;
+        let root = self.sets.find(index);
+        Some(&self.keys[root])
+    }
+
+    /// Merges the components containing `a` and `b`, adding either key if it
+    /// hasn't been seen. Returns false if they were already in the same
+    /// component.
+    pub fn union(&mut self, a: K, b: K) -> bool {
+        let a = self.insert(a);
+        let b = self.insert(b);
+        self.sets.union(a, b)
+    }
+
+    /// Returns true if `a` and `b` have both been seen and are in the same
+    /// component.
+    pub fn connected(&mut self, a: &K, b: &K) -> bool {
+        match (self.indexes.get(a), self.indexes.get(b)) {
+            (Some(&a), Some(&b)) => self.sets.connected(a, b),
+            _ => false,
+        }
+    }
+
+    /// Returns the number of keys in the component containing `key`, or 0 if
+    /// the key hasn't been seen.
+    pub fn size(&mut self, key: &K) -> usize {
+        self.indexes
+            .get(key)
+            .map_or(0, |&index| self.sets.size(index))
+    }
+
+    /// Returns the number of components.
+    pub fn component_count(&self) -> usize {
+        self.sets.component_count()
+    }
+
+    /// Returns the keys in the component containing `key`, in the order they
+    /// were first seen.
+    pub fn members(&mut self, key: &K) -> Vec<&K> {
+        let Some(&index) = self.indexes.get(key) else {
+            return Vec::new();
+        };
+        self.sets
+            .members(index)
+            .into_iter()
+            .map(|index| &self.keys[index])
+            .collect()
+    }
+
+    /// Returns every component as a list of its keys, in the order the keys
+    /// were first seen.
+    pub fn components(&mut self) -> Vec<Vec<&K>> {
+        self.sets
+            .components()
+            .into_iter()
+            .map(|component| component.into_iter().map(|i| &self.keys[i]).collect())
+            .collect()
+    }
+}
+
+impl<K: Clone + Eq + Hash> Default for KeyedUnionFind<K> {
+    fn default() -> Self {
+        KeyedUnionFind::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_union() {
+        let mut sets = UnionFind::new(6);
+        assert_eq!(sets.component_count(), 6);
+        assert!(sets.union(0, 1));
+        assert!(sets.union(2, 3));
+        assert!(sets.union(1, 3));
+        assert!(!sets.union(0, 2));
+        assert_eq!(sets.component_count(), 3);
+
+        assert!(sets.connected(0, 3));
+        assert!(!sets.connected(0, 4));
+        assert_eq!(sets.size(2), 4);
+        assert_eq!(sets.size(5), 1);
+        assert_eq!(sets.members(3), vec![0, 1, 2, 3]);
+        assert_eq!(sets.components(), vec![vec![0, 1, 2, 3], vec![4], vec![5]]);
+    }
+
+    #[test]
+    fn test_add() {
+        let mut sets = UnionFind::new(0);
+        assert!(sets.is_empty());
+        let a = sets.add();
+        let b = sets.add();
+        assert_eq!(sets.component_count(), 2);
+        sets.union(a, b);
+        assert_eq!(sets.component_count(), 1);
+        assert_eq!(sets.len(), 2);
+    }
+
+    #[test]
+    fn test_long_chain() {
+        let mut sets = UnionFind::new(1000);
+        for index in 1..1000 {
+            sets.union(index - 1, index);
+        }
+        assert_eq!(sets.component_count(), 1);
+        assert_eq!(sets.size(0), 1000);
+        assert!(sets.connected(0, 999));
+    }
+
+    #[test]
+    fn test_keyed() {
+        let mut sets = KeyedUnionFind::new();
+        sets.union("jqt", "rhn");
+        sets.union("rhn", "xhk");
+        sets.union("cmg", "qnr");
+        sets.insert("lsr");
+        assert_eq!(sets.len(), 6);
+        assert_eq!(sets.component_count(), 3);
+
+        assert!(sets.connected(&"jqt", &"xhk"));
+        assert!(!sets.connected(&"jqt", &"cmg"));
+        assert!(!sets.connected(&"jqt", &"zzz"));
+        assert_eq!(sets.size(&"xhk"), 3);
+        assert_eq!(sets.size(&"zzz"), 0);
+        assert_eq!(sets.find(&"zzz"), None);
+        let root = sets.find(&"jqt").copied();
+        assert_eq!(sets.find(&"rhn").copied(), root);
+        assert_eq!(sets.members(&"qnr"), vec![&"cmg", &"qnr"]);
+        assert_eq!(
+            sets.components(),
+            vec![
+                vec![&"jqt", &"rhn", &"xhk"],
+                vec![&"cmg", &"qnr"],
+                vec![&"lsr"]
+            ]
+        );
+    }
+}
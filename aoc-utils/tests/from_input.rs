@@ -0,0 +1,143 @@
+use aoc_utils::ScanError;
+use aoc_utils_derive::FromInput;
+
+#[derive(Debug, PartialEq, Eq, FromInput)]
+#[pattern("{name} = ({left}, {right})")]
+struct Node {
+    name: String,
+    left: String,
+    right: String,
+}
+
+#[derive(Debug, PartialEq, Eq, FromInput)]
+#[pattern("{x} {y} {z}")]
+struct Point {
+    x: i64,
+    y: i64,
+    z: i64,
+}
+
+#[derive(Debug, PartialEq, Eq, FromInput)]
+#[pattern("Card {id}: {winning} | {ours}")]
+struct Card {
+    id: u32,
+    winning: Vec<u32>,
+    ours: Vec<u32>,
+}
+
+#[derive(Debug, PartialEq, Eq, FromInput)]
+#[pattern("{name}: {values}")]
+struct List {
+    name: String,
+    #[separator(",")]
+    values: Vec<i32>,
+}
+
+#[derive(Debug, PartialEq, Eq, FromInput)]
+#[pattern("{from} -> {to}")]
+struct Line {
+    from: Pair,
+    to: Pair,
+}
+
+#[derive(Debug, PartialEq, Eq, FromInput)]
+#[pattern("{a},{b}")]
+struct Pair {
+    a: u32,
+    b: u32,
+}
+
+#[test]
+fn test_node() {
+    let node: Node = "AAA = (BBB, CCC)".parse().unwrap();
+    assert_eq!(
+        node,
+        Node {
+            name: "AAA".to_string(),
+            left: "BBB".to_string(),
+            right: "CCC".to_string(),
+        }
+    );
+    let spaced: Node = "  AAA=( BBB ,CCC )  ".parse().unwrap();
+    assert_eq!(spaced, node);
+}
+
+#[test]
+fn test_numbers() {
+    let point: Point = "1 -2   30".parse().unwrap();
+    assert_eq!(point, Point { x: 1, y: -2, z: 30 });
+}
+
+#[test]
+fn test_lists() {
+    let card: Card = "Card   1: 41 48 83 | 83 86  6".parse().unwrap();
+    assert_eq!(
+        card,
+        Card {
+            id: 1,
+            winning: vec![41, 48, 83],
+            ours: vec![83, 86, 6],
+        }
+    );
+
+    let list: List = "primes: 2, 3,5 , -7".parse().unwrap();
+    assert_eq!(list.name, "primes");
+    assert_eq!(list.values, vec![2, 3, 5, -7]);
+}
+
+#[test]
+fn test_nested() {
+    let line: Line = "0,9 -> 5,9".parse().unwrap();
+    assert_eq!(
+        line,
+        Line {
+            from: Pair { a: 0, b: 9 },
+            to: Pair { a: 5, b: 9 },
+        }
+    );
+}
+
+#[derive(Debug, PartialEq, Eq, FromInput)]
+#[pattern("{from} -> {to}")]
+struct Move {
+    from: i32,
+    to: i32,
+}
+
+#[test]
+fn test_negative_before_arrow() {
+    let step: Move = "-5 -> -3".parse().unwrap();
+    assert_eq!(step, Move { from: -5, to: -3 });
+    let step: Move = "4->-1".parse().unwrap();
+    assert_eq!(step, Move { from: 4, to: -1 });
+}
+
+#[test]
+fn test_errors() {
+    let error = "AAA = [BBB, CCC)".parse::<Node>().unwrap_err();
+    assert_eq!(error, ScanError::new(0, r#"expected "= (" after `name`"#));
+
+    let error = "AAA = (BBB, CCC]".parse::<Node>().unwrap_err();
+    assert_eq!(error, ScanError::new(12, r#"expected ")" after `right`"#));
+
+    let error = "AAA - (BBB, CCC)".parse::<Node>().unwrap_err();
+    assert_eq!(error, ScanError::new(0, r#"expected "= (" after `name`"#));
+
+    let error = "AAA = (BBB CCC)".parse::<Node>().unwrap_err();
+    assert_eq!(error, ScanError::new(7, r#"expected "," after `left`"#));
+
+    let error = "1 x 3".parse::<Point>().unwrap_err();
+    assert_eq!(error.position, 2);
+    assert!(error.message.starts_with("bad value for `y`"));
+
+    let error = "1 2".parse::<Point>().unwrap_err();
+    assert_eq!(error, ScanError::new(3, "expected a value for `z`"));
+
+    // The last field runs to the end of the input
+    let error = "1 2 3 4".parse::<Point>().unwrap_err();
+    assert_eq!(error.position, 4);
+    assert!(error.message.starts_with(r#"bad value for `z`: "3 4""#));
+
+    let error = "Card 1: 41 x | 83".parse::<Card>().unwrap_err();
+    assert_eq!(error.position, 11);
+}
@@ -1,20 +1,22 @@
+use std::io::BufRead;
+
+use aoc_utils::{each_line, run};
+
 fn main() {
-    let input = include_str!("./input.txt");
-    let output = process(input);
+    let output = run(include_str!("./input.txt"), process_reader);
     dbg!(output);
 }
 
-fn process(input: &str) -> String {
-    let mut lines = input.lines();
+fn process_reader(reader: impl BufRead) -> String {
     let mut result: usize = 0;
-    while let Some(line) = lines.next() {
+    each_line(reader, |line| {
         let mut value = String::new();
         let c1 = find_first_digit(line);
         value.push(c1);
         let c2 = find_last_digit(line);
         value.push(c2);
         result += value.parse::<usize>().unwrap();
-    }
+    });
     result.to_string()
 }
 
@@ -42,6 +44,10 @@ fn find_last_digit(text: &str) -> char {
 mod tests {
     use super::*;
 
+    fn process(input: &str) -> String {
+        process_reader(input.as_bytes())
+    }
+
     #[test]
     fn it_works() {
         let result = process("1abc2
@@ -1,152 +1,135 @@
-use regex::Regex;
+use std::io::BufRead;
 
-const EXPRESSION: &str = "([0-9]|one|two|three|four|five|six|seven|eight|nine)";
-const REV_EXPRESSION: &str = "([0-9]|eno|owt|eerht|ruof|evif|xis|neves|thgie|enin)";
+use aoc_utils::{each_line, run, Matcher};
+
+// The digit for a pattern is its index modulo 9, plus 1
+const DIGITS: [&str; 18] = [
+    "1", "2", "3", "4", "5", "6", "7", "8", "9", "one", "two", "three", "four", "five", "six",
+    "seven", "eight", "nine",
+];
 
 fn main() {
-    let input = include_str!("./input.txt");
-    let output = process(input);
+    let output = run(include_str!("./input.txt"), process_reader);
     dbg!(output);
 }
 
-fn process(input: &str) -> String {
-    let re = Regex::new(EXPRESSION).unwrap();
-    let rre = Regex::new(REV_EXPRESSION).unwrap();
+fn process_reader(reader: impl BufRead) -> String {
+    let matcher = Matcher::new(DIGITS);
 
-    let mut lines = input.lines();
     let mut result: usize = 0;
-    while let Some(line) = lines.next() {
-        let mut value = String::new();
-        
-        let c1 = find_first_digit(line, &re);
-        value.push(c1);
-        let c2 = find_last_digit(line, &rre);
-        value.push(c2);
-
-        result += value.parse::<usize>().unwrap();
-    }
+    each_line(reader, |line| {
+        let first = find_first_digit(line, &matcher);
+        let last = find_last_digit(line, &matcher);
+        result += first * 10 + last;
+    });
     result.to_string()
 }
 
-fn find_first_digit(text: &str, re: &Regex) -> char {
-    let Some(caps) = re.captures(text) else {
+fn find_first_digit(text: &str, matcher: &Matcher) -> usize {
+    let Some(found) = matcher.find_overlapping(text).min_by_key(|m| m.start) else {
         panic!("no digit in string \"{text}\"")
     };
-    match caps.get(1).map_or("", |m| m.as_str()) {
-        "one" | "1" => '1',
-        "two" | "2" => '2',
-        "three" | "3" => '3',
-        "four" | "4" => '4',
-        "five" | "5" => '5',
-        "six" | "6" => '6',
-        "seven" | "7" => '7',
-        "eight" | "8" => '8',
-        "nine" | "9" => '9',
-
-        _ => {
-            panic!("no digit in string \"{text}\"")
-        }
-    }
+    found.pattern % 9 + 1
 }
 
-fn find_last_digit(text: &str, re: &Regex) -> char {
-    let mut chars: Vec<char> = text.chars().collect();
-    chars.reverse();
-    let rtext: String = chars.iter().collect();
-
-    let Some(caps) = re.captures(&rtext) else {
+fn find_last_digit(text: &str, matcher: &Matcher) -> usize {
+    // Matches can overlap, e.g. "eightwo", so the last digit is the one that
+    // starts last rather than the one that ends last
+    let Some(found) = matcher.find_overlapping(text).max_by_key(|m| m.start) else {
         panic!("no digit in string \"{text}\"")
     };
-    match caps.get(1).map_or("", |m| m.as_str()) {
-        "eno" | "1" => '1',
-        "owt" | "2" => '2',
-        "eerht" | "3" => '3',
-        "ruof" | "4" => '4',
-        "evif" | "5" => '5',
-        "xis" | "6" => '6',
-        "neves" | "7" => '7',
-        "thgie" | "8" => '8',
-        "enin" | "9" => '9',
-
-        _ => {
-            panic!("no digit in string \"{text}\"")
-        }
-    }
+    found.pattern % 9 + 1
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn process(input: &str) -> String {
+        process_reader(input.as_bytes())
+    }
+
     #[test]
     fn test_find_first_digit_one() {
-        let re = Regex::new(EXPRESSION).unwrap();
-        let result = find_first_digit("aonea", &re);
-        assert_eq!(result, '1');
+        let matcher = Matcher::new(DIGITS);
+        let result = find_first_digit("aonea", &matcher);
+        assert_eq!(result, 1);
     }
 
     #[test]
     fn test_find_first_digit_digit() {
-        let re = Regex::new(EXPRESSION).unwrap();
-        let result = find_first_digit("aonea", &re);
-        assert_eq!(result, '1');
-        let result = find_first_digit("atwoa", &re);
-        assert_eq!(result, '2');
-        let result = find_first_digit("athreea", &re);
-        assert_eq!(result, '3');
-        let result = find_first_digit("afoura", &re);
-        assert_eq!(result, '4');
-        let result = find_first_digit("afivea", &re);
-        assert_eq!(result, '5');
-        let result = find_first_digit("asixa", &re);
-        assert_eq!(result, '6');
-        let result = find_first_digit("asevena", &re);
-        assert_eq!(result, '7');
-        let result = find_first_digit("aeighta", &re);
-        assert_eq!(result, '8');
-        let result = find_first_digit("aninea", &re);
-        assert_eq!(result, '9');
+        let matcher = Matcher::new(DIGITS);
+        let result = find_first_digit("aonea", &matcher);
+        assert_eq!(result, 1);
+        let result = find_first_digit("atwoa", &matcher);
+        assert_eq!(result, 2);
+        let result = find_first_digit("athreea", &matcher);
+        assert_eq!(result, 3);
+        let result = find_first_digit("afoura", &matcher);
+        assert_eq!(result, 4);
+        let result = find_first_digit("afivea", &matcher);
+        assert_eq!(result, 5);
+        let result = find_first_digit("asixa", &matcher);
+        assert_eq!(result, 6);
+        let result = find_first_digit("asevena", &matcher);
+        assert_eq!(result, 7);
+        let result = find_first_digit("aeighta", &matcher);
+        assert_eq!(result, 8);
+        let result = find_first_digit("aninea", &matcher);
+        assert_eq!(result, 9);
     }
 
     #[test]
     fn test_find_last_digit() {
-        let re = Regex::new(REV_EXPRESSION).unwrap();
-        let result = find_last_digit("aonea", &re);
-        assert_eq!(result, '1');
-        let result = find_last_digit("atwoa", &re);
-        assert_eq!(result, '2');
-        let result = find_last_digit("athreea", &re);
-        assert_eq!(result, '3');
-        let result = find_last_digit("afoura", &re);
-        assert_eq!(result, '4');
-        let result = find_last_digit("afivea", &re);
-        assert_eq!(result, '5');
-        let result = find_last_digit("asixa", &re);
-        assert_eq!(result, '6');
-        let result = find_last_digit("asevena", &re);
-        assert_eq!(result, '7');
-        let result = find_last_digit("aeighta", &re);
-        assert_eq!(result, '8');
-        let result = find_last_digit("aninea", &re);
-        assert_eq!(result, '9');
+        let matcher = Matcher::new(DIGITS);
+        let result = find_last_digit("aonea", &matcher);
+        assert_eq!(result, 1);
+        let result = find_last_digit("atwoa", &matcher);
+        assert_eq!(result, 2);
+        let result = find_last_digit("athreea", &matcher);
+        assert_eq!(result, 3);
+        let result = find_last_digit("afoura", &matcher);
+        assert_eq!(result, 4);
+        let result = find_last_digit("afivea", &matcher);
+        assert_eq!(result, 5);
+        let result = find_last_digit("asixa", &matcher);
+        assert_eq!(result, 6);
+        let result = find_last_digit("asevena", &matcher);
+        assert_eq!(result, 7);
+        let result = find_last_digit("aeighta", &matcher);
+        assert_eq!(result, 8);
+        let result = find_last_digit("aninea", &matcher);
+        assert_eq!(result, 9);
     }
 
     #[test]
     fn test_find_last_digit_digit() {
-        let re = Regex::new(REV_EXPRESSION).unwrap();
-        let result = find_last_digit("a1a", &re);
-        assert_eq!(result, '1');
+        let matcher = Matcher::new(DIGITS);
+        let result = find_last_digit("a1a", &matcher);
+        assert_eq!(result, 1);
+    }
+
+    #[test]
+    fn test_overlapping_digits() {
+        let matcher = Matcher::new(DIGITS);
+        assert_eq!(find_first_digit("eightwo", &matcher), 8);
+        assert_eq!(find_last_digit("eightwo", &matcher), 2);
+        assert_eq!(find_last_digit("1oneight", &matcher), 8);
+        assert_eq!(find_first_digit("7", &matcher), 7);
+        assert_eq!(find_last_digit("7", &matcher), 7);
     }
 
     #[test]
     fn it_works() {
-        let result = process("two1nine
+        let result = process(
+            "two1nine
 eightwothree
 abcone2threexyz
 xtwone3four
 4nineeightseven2
 zoneight234
-7pqrstsixteen");
+7pqrstsixteen",
+        );
         assert_eq!(result, "281".to_string());
     }
 }
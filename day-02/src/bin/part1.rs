@@ -1,29 +1,25 @@
+use std::io::BufRead;
+
+use aoc_utils::{each_line, run, scan};
+
 fn main() {
-    let input = include_str!("./input.txt");
-    let output = process(input);
+    let output = run(include_str!("./input.txt"), process_reader);
     dbg!(output);
 }
 
-fn process(input: &str) -> String {
-    let mut games = input.lines();
+fn process_reader(reader: impl BufRead) -> String {
     let mut result: usize = 0;
-    while let Some(game) = games.next() {
+    each_line(reader, |game| {
         result += parse_game(game);
-    }
+    });
     result.to_string()
 }
 
 fn parse_game(game: &str) -> usize {
-    let input = &game["Game ".len()..];
-    let mut iter = input.split(':');
-    let id: usize = iter
-        .next()
-        .expect("can't find id in \"{game}\"")
-        .parse()
-        .unwrap();
+    let (id, samples) = scan!(game, "Game {id}: {samples}", id: usize, samples: &str)
+        .unwrap_or_else(|error| panic!(r#"bad game "{game}": {error}"#));
 
-    let mut samples = iter.next().unwrap().split(';');
-    while let Some(sample) = samples.next() {
+    for sample in samples.split(';') {
         if is_impossible(sample) {
             return 0;
         }
@@ -33,11 +29,9 @@ fn parse_game(game: &str) -> usize {
 }
 
 fn is_impossible(sample: &str) -> bool {
-    let mut color_values = sample.split(',');
-    while let Some(color_value) = color_values.next() {
-        let mut cv = color_value.trim().split_ascii_whitespace();
-        let value: usize = cv.next().unwrap().parse().unwrap();
-        let color = cv.next().unwrap();
+    for color_value in sample.split(',') {
+        let (value, color) = scan!(color_value, "{value} {color}", value: usize, color: &str)
+            .unwrap_or_else(|error| panic!(r#"bad sample "{sample}": {error}"#));
         match color {
             "red" => {
                 if value > 12 {
@@ -70,6 +64,10 @@ fn is_impossible(sample: &str) -> bool {
 mod tests {
     use super::*;
 
+    fn process(input: &str) -> String {
+        process_reader(input.as_bytes())
+    }
+
     #[test]
     fn test_is_impossible() {
         assert!(!is_impossible(" 2 red"));
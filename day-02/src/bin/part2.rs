@@ -1,33 +1,30 @@
 use std::collections::HashMap;
+use std::io::BufRead;
+
+use aoc_utils::{each_line, run, scan};
 
 fn main() {
-    let input = include_str!("./input.txt");
-    let output = process(input);
+    let output = run(include_str!("./input.txt"), process_reader);
     dbg!(output);
 }
 
-fn process(input: &str) -> String {
-    let mut games = input.lines();
+fn process_reader(reader: impl BufRead) -> String {
     let mut result: usize = 0;
-    while let Some(game) = games.next() {
+    each_line(reader, |game| {
         result += parse_game(game);
-    }
+    });
     result.to_string()
 }
 
 fn parse_game(game: &str) -> usize {
-    let input = &game["Game ".len()..];
-    let mut iter = input.split(':');
-
-    // Skip the id
-    iter.next();
+    let (_, samples) = scan!(game, "Game {id}: {samples}", id: usize, samples: &str)
+        .unwrap_or_else(|error| panic!(r#"bad game "{game}": {error}"#));
 
     let mut map: HashMap<&str, usize> = HashMap::new();
     map.insert("red", 0);
     map.insert("green", 0);
     map.insert("blue", 0);
-    let mut samples = iter.next().unwrap().split(';');
-    while let Some(sample) = samples.next() {
+    for sample in samples.split(';') {
         update_map(sample, &mut map);
     }
     let power = map["red"] * map["green"] * map["blue"];
@@ -35,12 +32,9 @@ fn parse_game(game: &str) -> usize {
 }
 
 fn update_map<'a>(sample: &'a str, map: &mut HashMap<&'a str, usize>) {
-    let mut color_values = sample.split(',');
-    while let Some(color_value) = color_values.next() {
-        let mut cv = color_value.trim().split_ascii_whitespace();
-
-        let value: usize = cv.next().unwrap().parse().unwrap();
-        let color = cv.next().unwrap();
+    for color_value in sample.split(',') {
+        let (value, color) = scan!(color_value, "{value} {color}", value: usize, color: &'a str)
+            .unwrap_or_else(|error| panic!(r#"bad sample "{sample}": {error}"#));
 
         let existing = map.get(color).unwrap_or(&0);
         if value > *existing {
@@ -53,6 +47,10 @@ fn update_map<'a>(sample: &'a str, map: &mut HashMap<&'a str, usize>) {
 mod tests {
     use super::*;
 
+    fn process(input: &str) -> String {
+        process_reader(input.as_bytes())
+    }
+
     #[test]
     fn test_update_map() {
         let mut map = HashMap::new();
@@ -1,28 +1,29 @@
+use std::io::BufRead;
+
+use aoc_utils::{each_line, ints, run, scan};
+
 fn main() {
-    let input = include_str!("./input.txt");
-    let output = process(input);
+    let output = run(include_str!("./input.txt"), process_reader);
     dbg!(output);
 }
 
-fn process(input: &str) -> String {
+fn process_reader(reader: impl BufRead) -> String {
     let mut result: u32 = 0;
-    for card in input.lines() {
-        result += process_card(card)
-    }
+    each_line(reader, |card| result += process_card(card));
     result.to_string()
 }
 
 fn process_card(card: &str) -> u32 {
-    let card = card.split(':').last().unwrap();
-    let numbers: Vec<&str> = card.split('|').collect();
-    let winning_numbers: Vec<&str> = numbers[0]
-        .split_ascii_whitespace()
-        .map(|n| n.trim())
-        .collect();
-    let our_numbers: Vec<&str> = numbers[1]
-        .split_ascii_whitespace()
-        .map(|n| n.trim())
-        .collect();
+    let (_, winning, ours) = scan!(
+        card,
+        "Card {id}: {winning} | {ours}",
+        id: u32,
+        winning: &str,
+        ours: &str
+    )
+    .unwrap_or_else(|error| panic!(r#"bad card "{card}": {error}"#));
+    let winning_numbers: Vec<u32> = ints(winning);
+    let our_numbers: Vec<u32> = ints(ours);
 
     let mut result: u32 = 0;
     for n in our_numbers.iter() {
@@ -38,6 +39,10 @@ fn process_card(card: &str) -> u32 {
 mod tests {
     use super::*;
 
+    fn process(input: &str) -> String {
+        process_reader(input.as_bytes())
+    }
+
     #[test]
     fn test_process_card() {
         let result = process_card("Card         1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53");
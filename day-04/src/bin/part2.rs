@@ -1,29 +1,30 @@
+use std::collections::VecDeque;
+use std::io::BufRead;
+
+use aoc_utils::{each_line, ints, run, scan};
+
 fn main() {
-    let input = include_str!("./input.txt");
-    let output = process(input);
+    let output = run(include_str!("./input.txt"), process_reader);
     dbg!(output);
 }
 
 #[derive(Clone, Copy, Debug)]
 struct Card {
-    id: usize,
     number_of_matches: usize,
 }
 
 impl Card {
     fn new(card: &str) -> Self {
-        let tmp: Vec<&str> = card[4..].trim().split(':').collect();
-        let id: usize = tmp[0].trim().parse().unwrap();
-        let numbers: Vec<&str> = tmp[1].split('|').collect();
-
-        let winning_numbers: Vec<&str> = numbers[0]
-            .split_ascii_whitespace()
-            .map(|n| n.trim())
-            .collect();
-        let our_numbers: Vec<&str> = numbers[1]
-            .split_ascii_whitespace()
-            .map(|n| n.trim())
-            .collect();
+        let (_, winning, ours) = scan!(
+            card,
+            "Card {id}: {winning} | {ours}",
+            id: usize,
+            winning: &str,
+            ours: &str
+        )
+        .unwrap_or_else(|error| panic!(r#"bad card "{card}": {error}"#));
+        let winning_numbers: Vec<u32> = ints(winning);
+        let our_numbers: Vec<u32> = ints(ours);
 
         let mut number_of_matches: usize = 0;
         for n in our_numbers.iter() {
@@ -32,39 +33,45 @@ impl Card {
             }
         }
 
-        Card {
-            id,
-            number_of_matches,
-        }
+        Card { number_of_matches }
     }
 }
 
-fn process(input: &str) -> String {
-    let mut cards: Vec<Card> = Vec::new();
-    for card in input.lines() {
-        cards.push(Card::new(card));
-    }
+fn process_reader(reader: impl BufRead) -> String {
+    // A card's matches only win copies of the next few cards, so only the
+    // copies won for those cards need to be kept
+    let mut pending_copies: VecDeque<usize> = VecDeque::new();
+    let mut total: usize = 0;
+
+    each_line(reader, |card| {
+        let card = Card::new(card);
 
-    let mut index: usize = 0;
-    while index < cards.len() {
-        let card = cards[index];
-        for i in card.id..(card.id + card.number_of_matches) {
-            cards.push(cards[i]);
+        // The original plus any copies won by earlier cards
+        let copies = 1 + pending_copies.pop_front().unwrap_or(0);
+        total += copies;
+
+        if pending_copies.len() < card.number_of_matches {
+            pending_copies.resize(card.number_of_matches, 0);
         }
-        index += 1;
-    }
+        for pending in pending_copies.iter_mut().take(card.number_of_matches) {
+            *pending += copies;
+        }
+    });
 
-    cards.len().to_string()
+    total.to_string()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn process(input: &str) -> String {
+        process_reader(input.as_bytes())
+    }
+
     #[test]
     fn test_new_card() {
         let card = Card::new("Card         1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53");
-        assert_eq!(card.id, 1);
         assert_eq!(card.number_of_matches, 4);
     }
 
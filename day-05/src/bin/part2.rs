@@ -1,4 +1,7 @@
-use std::{cmp, str::Lines};
+use std::ops;
+
+use aoc_utils::{ints, sections};
+use day_05::Maps;
 
 fn main() {
     let input = include_str!("./input.txt");
@@ -6,256 +9,99 @@ fn main() {
     dbg!(output);
 }
 
-#[derive(Clone, Copy, Debug)]
-struct Range {
-    length: u64,
-    source_start: u64,
-    destination_start: u64,
-}
-
-impl Range {
-    fn new(range: &str) -> Self {
-        let tmp: Vec<u64> = range
-            .split_ascii_whitespace()
-            .map(|n| n.parse().unwrap())
-            .collect();
-        Range {
-            length: tmp[2],
-            source_start: tmp[1],
-            destination_start: tmp[0],
-        }
-    }
-
-    fn convert(&self, source: u64) -> Option<u64> {
-        if source < self.source_start {
-            return None;
-        }
-        if source >= self.source_start + self.length {
-            return None;
-        }
-        Some(self.destination_start + (source - self.source_start))
-    }
-}
-
-struct Map {
-    ranges: Vec<Range>,
-}
-
-impl Map {
-    fn new(ranges: Vec<Range>) -> Self {
-        Map { ranges }
-    }
-
-    fn convert(&self, source: u64) -> u64 {
-        self.ranges
-            .iter()
-            .flat_map(|range| range.convert(source))
-            .next()
-            .unwrap_or(source)
-    }
-}
-
-struct Almanac {
-    maps: Vec<Map>,
-}
-
-impl Almanac {
-    fn new(maps: Vec<Map>) -> Self {
-        Almanac { maps }
-    }
-
-    fn convert(&self, source: u64) -> u64 {
-        self.maps
-            .iter()
-            .fold(source, |value, map| map.convert(value))
-    }
-}
-
 struct Seeds {
-    seed_ranges: Vec<(u64, u64)>,
+    seed_ranges: Vec<ops::Range<u64>>,
 }
 
 impl Seeds {
-    fn new(line: &str) -> Seeds {
-        let values: Vec<u64> = line
-            .split(':')
-            .last()
-            .unwrap()
-            .split_ascii_whitespace()
-            .map(|n| n.parse::<u64>().unwrap())
-            .collect();
+    fn new(values: &str) -> Seeds {
+        let values: Vec<u64> = ints(values);
 
-        let mut seed_ranges: Vec<(u64, u64)> = Vec::new();
+        let mut seed_ranges: Vec<ops::Range<u64>> = Vec::new();
         for index in (0..values.len()).step_by(2) {
-            seed_ranges.push((values[index], values[index + 1]));
+            seed_ranges.push(values[index]..values[index] + values[index + 1]);
         }
 
         Seeds { seed_ranges }
     }
 
-    fn iter(&self) -> SeedsIter {
-        SeedsIter {
-            seed_ranges: &self.seed_ranges,
-            current_range: 0,
-            index: 0,
-        }
-    }
-}
-
-struct SeedsIter<'a> {
-    seed_ranges: &'a Vec<(u64, u64)>,
-    current_range: usize,
-    index: usize,
-}
-
-impl<'a> Iterator for SeedsIter<'a> {
-    type Item = u64;
-
-    fn next(&mut self) -> Option<Self::Item> {
-        if self.current_range == self.seed_ranges.len() {
-            return None;
-        }
-
-        let seed = self.seed_ranges[self.current_range].0 + self.index as u64;
-        self.index += 1;
-        if self.index as u64 == self.seed_ranges[self.current_range].1 {
-            self.index = 0;
-            self.current_range += 1;
-        }
-        Some(seed)
+    fn contains(&self, seed: u64) -> bool {
+        self.seed_ranges.iter().any(|range| range.contains(&seed))
     }
 }
 
 fn process(input: &str) -> String {
-    let mut lines = input.lines();
-    let seeds = Seeds::new(lines.next().unwrap());
-
-    lines.next(); // consume blank line
-
-    let mut maps: Vec<Map> = Vec::new();
-    while let Some(_) = lines.next() {
-        process_map(&mut maps, &mut lines);
-    }
-
-    let almanac = Almanac::new(maps);
-    let result = seeds
+    let mut sections = sections(input);
+    let (_, seeds) = sections.next().unwrap();
+    let seeds = Seeds::new(seeds);
+
+    let almanac = Maps::parse(sections)
+        .and_then(|maps| maps.chain("seed", "location"))
+        .unwrap_or_else(|error| panic!("bad almanac: {error}"));
+    let result = almanac
+        .convert_ranges(&seeds.seed_ranges)
         .iter()
-        .map(|seed| almanac.convert(seed))
-        .reduce(|smallest, current| cmp::min(smallest, current))
+        .map(|range| range.start)
+        .min()
         .unwrap();
 
-    result.to_string()
-}
+    // Work back from the answer to check it comes from a seed in the ranges
+    assert!(
+        almanac
+            .invert(result)
+            .into_iter()
+            .any(|seed| seeds.contains(seed)),
+        "location {result} doesn't come from any seed"
+    );
 
-fn process_map(maps: &mut Vec<Map>, lines: &mut Lines) {
-    let mut ranges: Vec<Range> = Vec::new();
-    while let Some(line) = lines.map(|l| l.trim()).next() {
-        if line.len() == 0 {
-            break;
-        }
-        ranges.push(Range::new(line));
-    }
-    maps.push(Map::new(ranges));
+    result.to_string()
 }
 
 #[cfg(test)]
 mod tests {
-    use super::*;
+    use aoc_utils::example;
 
-    #[test]
-    fn test_range_new() {
-        let range = Range::new("50 98 2");
-        assert_eq!(range.destination_start, 50);
-        assert_eq!(range.source_start, 98);
-        assert_eq!(range.length, 2);
-    }
-
-    #[test]
-    fn test_range_convert() {
-        let range = Range::new("50 98 2");
-        assert_eq!(range.convert(97), None);
-        assert_eq!(range.convert(98), Some(50));
-        assert_eq!(range.convert(99), Some(51));
-        assert_eq!(range.convert(100), None);
-    }
-
-    #[test]
-    fn test_map_convert() {
-        let ranges = vec![Range::new("50 98 2"), Range::new("52 50 48")];
-        let map = Map::new(ranges);
-
-        assert_eq!(map.convert(0), 0);
-        assert_eq!(map.convert(1), 1);
-
-        assert_eq!(map.convert(48), 48);
-        assert_eq!(map.convert(49), 49);
-        assert_eq!(map.convert(50), 52);
-        assert_eq!(map.convert(51), 53);
-        assert_eq!(map.convert(52), 54);
-
-        assert_eq!(map.convert(95), 97);
-        assert_eq!(map.convert(96), 98);
-        assert_eq!(map.convert(97), 99);
-        assert_eq!(map.convert(98), 50);
-        assert_eq!(map.convert(99), 51);
-    }
-
-    #[test]
-    fn test_almanac() {
-        let mut maps: Vec<Map> = Vec::new();
-        let map = Map::new(vec![Range::new("50 98 2"), Range::new("52 50 48")]);
-        maps.push(map);
-        let map = Map::new(vec![
-            Range::new("0 15 37"),
-            Range::new("37 52 2"),
-            Range::new("39 0 15"),
-        ]);
-        maps.push(map);
-        let almanac = Almanac::new(maps);
-
-        assert_eq!(almanac.convert(79), 81);
-        assert_eq!(almanac.convert(14), 53);
-        assert_eq!(almanac.convert(55), 57);
-        assert_eq!(almanac.convert(13), 52);
-    }
+    use super::*;
 
     #[test]
     fn test_process() {
-        let result = process(
-            "seeds: 79 14 55 13
-
-             seed-to-soil map:
-             50 98 2
-             52 50 48
-             
-             soil-to-fertilizer map:
-             0 15 37
-             37 52 2
-             39 0 15
-             
-             fertilizer-to-water map:
-             49 53 8
-             0 11 42
-             42 0 7
-             57 7 4
-             
-             water-to-light map:
-             88 18 7
-             18 25 70
-             
-             light-to-temperature map:
-             45 77 23
-             81 45 19
-             68 64 13
-             
-             temperature-to-humidity map:
-             0 69 1
-             1 0 69
-             
-             humidity-to-location map:
-             60 56 37
-             56 93 4",
-        );
+        let result = process(example!(
+            "
+            seeds: 79 14 55 13
+
+            seed-to-soil map:
+            50 98 2
+            52 50 48
+
+            soil-to-fertilizer map:
+            0 15 37
+            37 52 2
+            39 0 15
+
+            fertilizer-to-water map:
+            49 53 8
+            0 11 42
+            42 0 7
+            57 7 4
+
+            water-to-light map:
+            88 18 7
+            18 25 70
+
+            light-to-temperature map:
+            45 77 23
+            81 45 19
+            68 64 13
+
+            temperature-to-humidity map:
+            0 69 1
+            1 0 69
+
+            humidity-to-location map:
+            60 56 37
+            56 93 4
+            "
+        ));
         assert_eq!(result, "46".to_string());
     }
 }
@@ -0,0 +1,18 @@
+use aoc_utils::sections;
+use day_05::Maps;
+
+// Prints the whole almanac composed into one table, showing how far each run
+// of seeds is shifted to get its location, after anything questionable about
+// its maps
+fn main() {
+    let input = include_str!("./input.txt");
+    let maps =
+        Maps::parse(sections(input).skip(1)).unwrap_or_else(|error| panic!("bad almanac: {error}"));
+    for problem in maps.validate("seed", "location") {
+        eprintln!("warning: {problem}");
+    }
+    let almanac = maps
+        .chain("seed", "location")
+        .unwrap_or_else(|error| panic!("bad almanac: {error}"));
+    print!("{}", almanac.compose());
+}
@@ -0,0 +1,575 @@
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::error::Error;
+use std::fmt;
+use std::ops;
+
+use aoc_utils::{FromInput, ScanError};
+
+mod piecewise;
+
+pub use piecewise::{PiecewiseMap, Segment};
+
+#[derive(Clone, Copy, Debug, FromInput)]
+#[pattern("{destination_start} {source_start} {length}")]
+pub struct Range {
+    length: u64,
+    source_start: u64,
+    destination_start: u64,
+}
+
+impl Range {
+    pub fn new(range: &str) -> Self {
+        range
+            .parse()
+            .unwrap_or_else(|error| panic!(r#"bad range "{range}": {error}"#))
+    }
+
+    pub fn convert(&self, source: u64) -> Option<u64> {
+        if source < self.source_start {
+            return None;
+        }
+        if source >= self.source_start + self.length {
+            return None;
+        }
+        Some(self.destination_start + (source - self.source_start))
+    }
+
+    fn invert(&self, destination: u64) -> Option<u64> {
+        if destination < self.destination_start {
+            return None;
+        }
+        if destination >= self.destination_start + self.length {
+            return None;
+        }
+        Some(self.source_start + (destination - self.destination_start))
+    }
+}
+
+#[derive(Clone)]
+pub struct Map {
+    ranges: Vec<Range>,
+}
+
+impl Map {
+    pub fn new(ranges: Vec<Range>) -> Self {
+        Map { ranges }
+    }
+
+    pub fn convert(&self, source: u64) -> u64 {
+        self.ranges
+            .iter()
+            .flat_map(|range| range.convert(source))
+            .next()
+            .unwrap_or(source)
+    }
+
+    /// Converts every number in `sources` at once, splitting them where they
+    /// cross the edges of the ranges. The ranges that come out may overlap.
+    pub fn convert_ranges(&self, sources: &[ops::Range<u64>]) -> Vec<ops::Range<u64>> {
+        let mut converted: Vec<ops::Range<u64>> = Vec::new();
+        let mut unconverted: Vec<ops::Range<u64>> = sources
+            .iter()
+            .filter(|source| !source.is_empty())
+            .cloned()
+            .collect();
+        for range in &self.ranges {
+            let mut remaining: Vec<ops::Range<u64>> = Vec::new();
+            for source in unconverted {
+                let start = source.start.max(range.source_start);
+                let end = source.end.min(range.source_start + range.length);
+                if start >= end {
+                    remaining.push(source);
+                    continue;
+                }
+                let destination = range.destination_start + (start - range.source_start);
+                converted.push(destination..destination + (end - start));
+                if source.start < start {
+                    remaining.push(source.start..start);
+                }
+                if end < source.end {
+                    remaining.push(end..source.end);
+                }
+            }
+            unconverted = remaining;
+        }
+
+        // Anything the ranges don't cover keeps its number
+        converted.extend(unconverted);
+        converted
+    }
+
+    /// Returns every source that converts to `destination`, in order. The
+    /// ranges needn't map sources to destinations one to one, so there may
+    /// be none or several.
+    pub fn invert(&self, destination: u64) -> Vec<u64> {
+        let mut sources: Vec<u64> = self
+            .ranges
+            .iter()
+            .flat_map(|range| range.invert(destination))
+            .collect();
+        sources.push(destination);
+
+        // A candidate may be claimed by an earlier range, or not be left
+        // alone after all
+        sources.retain(|&source| self.convert(source) == destination);
+        sources.sort_unstable();
+        sources.dedup();
+        sources
+    }
+}
+
+pub struct Almanac {
+    maps: Vec<Map>,
+}
+
+impl Almanac {
+    pub fn new(maps: Vec<Map>) -> Self {
+        Almanac { maps }
+    }
+
+    pub fn convert(&self, source: u64) -> u64 {
+        self.maps
+            .iter()
+            .fold(source, |value, map| map.convert(value))
+    }
+
+    /// Converts whole ranges of numbers through every map.
+    pub fn convert_ranges(&self, sources: &[ops::Range<u64>]) -> Vec<ops::Range<u64>> {
+        self.maps
+            .iter()
+            .fold(sources.to_vec(), |values, map| map.convert_ranges(&values))
+    }
+
+    /// Works back from a number at the end of the almanac to every number at
+    /// the start that converts to it.
+    pub fn invert(&self, destination: u64) -> Vec<u64> {
+        self.maps
+            .iter()
+            .rev()
+            .fold(vec![destination], |values, map| {
+                let mut sources: Vec<u64> =
+                    values.iter().flat_map(|&value| map.invert(value)).collect();
+                sources.sort_unstable();
+                sources.dedup();
+                sources
+            })
+    }
+}
+
+/// Something wrong with the maps in an almanac.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum AlmanacError {
+    /// A map's header isn't like `seed-to-soil map`
+    BadHeader(String),
+    /// A line of a map isn't a range like `50 98 2`
+    BadRange { line: String, error: ScanError },
+    /// There's more than one map between the same two categories
+    Duplicate { source: String, destination: String },
+    /// No maps lead from one category to the other
+    BrokenChain { from: String, to: String },
+    /// Two of a map's ranges cover the same sources, so only the first
+    /// one listed applies to them
+    Overlap {
+        source: String,
+        destination: String,
+        first: ops::Range<u64>,
+        second: ops::Range<u64>,
+    },
+    /// None of a map's ranges cover the sources between two of them, so
+    /// those keep their numbers
+    Gap {
+        source: String,
+        destination: String,
+        gap: ops::Range<u64>,
+    },
+}
+
+impl fmt::Display for AlmanacError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AlmanacError::BadHeader(header) => write!(f, r#"bad map header "{header}""#),
+            AlmanacError::BadRange { line, error } => write!(f, r#"bad range "{line}": {error}"#),
+            AlmanacError::Duplicate {
+                source,
+                destination,
+            } => write!(f, "more than one {source}-to-{destination} map"),
+            AlmanacError::BrokenChain { from, to } => {
+                write!(f, "no maps lead from {from} to {to}")
+            }
+            AlmanacError::Overlap {
+                source,
+                destination,
+                first,
+                second,
+            } => write!(
+                f,
+                "{source}-to-{destination} map: source ranges {first:?} and {second:?} overlap"
+            ),
+            AlmanacError::Gap {
+                source,
+                destination,
+                gap,
+            } => write!(
+                f,
+                "{source}-to-{destination} map: no range covers the sources {gap:?}"
+            ),
+        }
+    }
+}
+
+impl Error for AlmanacError {}
+
+/// The maps in an almanac, keyed by the categories they convert from and to,
+/// so they can be chained by name whatever order they're listed in.
+pub struct Maps {
+    maps: BTreeMap<(String, String), Map>,
+}
+
+impl Maps {
+    /// Reads the maps from the almanac's sections, which are headed like
+    /// `seed-to-soil map`.
+    pub fn parse<'a>(
+        sections: impl Iterator<Item = (&'a str, &'a str)>,
+    ) -> Result<Self, AlmanacError> {
+        let mut maps = BTreeMap::new();
+        for (header, ranges) in sections {
+            let (source, destination) = header
+                .strip_suffix(" map")
+                .and_then(|categories| categories.split_once("-to-"))
+                .ok_or_else(|| AlmanacError::BadHeader(header.to_string()))?;
+            let key = (source.to_string(), destination.to_string());
+            let ranges = ranges
+                .lines()
+                .map(|line| {
+                    line.parse::<Range>()
+                        .map_err(|error| AlmanacError::BadRange {
+                            line: line.to_string(),
+                            error,
+                        })
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            let map = Map::new(ranges);
+            if maps.insert(key, map).is_some() {
+                return Err(AlmanacError::Duplicate {
+                    source: source.to_string(),
+                    destination: destination.to_string(),
+                });
+            }
+        }
+        Ok(Maps { maps })
+    }
+
+    // Finds the fewest maps that lead from one category to another
+    fn path(&self, from: &str, to: &str) -> Result<Vec<&(String, String)>, AlmanacError> {
+        // The map each category was first reached by
+        let mut reached_by: HashMap<&str, &(String, String)> = HashMap::new();
+        let mut queue: VecDeque<&str> = VecDeque::from([from]);
+        while let Some(category) = queue.pop_front() {
+            if category == to {
+                let mut path = Vec::new();
+                let mut category = to;
+                while category != from {
+                    let key = reached_by[category];
+                    path.push(key);
+                    category = &key.0;
+                }
+                path.reverse();
+                return Ok(path);
+            }
+            for key in self.maps.keys().filter(|(source, _)| source == category) {
+                let destination = key.1.as_str();
+                if destination != from && !reached_by.contains_key(destination) {
+                    reached_by.insert(destination, key);
+                    queue.push_back(destination);
+                }
+            }
+        }
+        Err(AlmanacError::BrokenChain {
+            from: from.to_string(),
+            to: to.to_string(),
+        })
+    }
+
+    /// Returns the almanac that converts from one category to another by
+    /// chaining the maps between them.
+    pub fn chain(&self, from: &str, to: &str) -> Result<Almanac, AlmanacError> {
+        let maps = self
+            .path(from, to)?
+            .into_iter()
+            .map(|key| self.maps[key].clone())
+            .collect();
+        Ok(Almanac::new(maps))
+    }
+
+    /// Returns everything questionable about the maps: ranges that overlap
+    /// or leave gaps between them, and whether any maps lead from one
+    /// category to the other. Gaps are allowed by the puzzle, so it's up to
+    /// the caller which of these matter.
+    pub fn validate(&self, from: &str, to: &str) -> Vec<AlmanacError> {
+        let mut problems: Vec<AlmanacError> = Vec::new();
+        for ((source, destination), map) in &self.maps {
+            let mut ranges: Vec<ops::Range<u64>> = map
+                .ranges
+                .iter()
+                .map(|range| range.source_start..range.source_start + range.length)
+                .collect();
+            ranges.sort_by_key(|range| (range.start, range.end));
+
+            // Compare each range with the one reaching furthest before it
+            let mut ranges = ranges.into_iter();
+            let Some(mut furthest) = ranges.next() else {
+                continue;
+            };
+            for range in ranges {
+                if range.start < furthest.end {
+                    problems.push(AlmanacError::Overlap {
+                        source: source.clone(),
+                        destination: destination.clone(),
+                        first: furthest.clone(),
+                        second: range.clone(),
+                    });
+                } else if range.start > furthest.end {
+                    problems.push(AlmanacError::Gap {
+                        source: source.clone(),
+                        destination: destination.clone(),
+                        gap: furthest.end..range.start,
+                    });
+                }
+                if range.end > furthest.end {
+                    furthest = range;
+                }
+            }
+        }
+        if let Err(error) = self.path(from, to) {
+            problems.push(error);
+        }
+        problems
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use aoc_utils::{example, sections};
+
+    use super::*;
+
+    #[test]
+    fn test_range_new() {
+        let range = Range::new("50 98 2");
+        assert_eq!(range.destination_start, 50);
+        assert_eq!(range.source_start, 98);
+        assert_eq!(range.length, 2);
+    }
+
+    #[test]
+    fn test_range_convert() {
+        let range = Range::new("50 98 2");
+        assert_eq!(range.convert(97), None);
+        assert_eq!(range.convert(98), Some(50));
+        assert_eq!(range.convert(99), Some(51));
+        assert_eq!(range.convert(100), None);
+    }
+
+    #[test]
+    fn test_map_convert() {
+        let ranges = vec![Range::new("50 98 2"), Range::new("52 50 48")];
+        let map = Map::new(ranges);
+
+        assert_eq!(map.convert(0), 0);
+        assert_eq!(map.convert(1), 1);
+
+        assert_eq!(map.convert(48), 48);
+        assert_eq!(map.convert(49), 49);
+        assert_eq!(map.convert(50), 52);
+        assert_eq!(map.convert(51), 53);
+        assert_eq!(map.convert(52), 54);
+
+        assert_eq!(map.convert(95), 97);
+        assert_eq!(map.convert(96), 98);
+        assert_eq!(map.convert(97), 99);
+        assert_eq!(map.convert(98), 50);
+        assert_eq!(map.convert(99), 51);
+    }
+
+    #[test]
+    fn test_almanac() {
+        let mut maps: Vec<Map> = Vec::new();
+        let map = Map::new(vec![Range::new("50 98 2"), Range::new("52 50 48")]);
+        maps.push(map);
+        let map = Map::new(vec![
+            Range::new("0 15 37"),
+            Range::new("37 52 2"),
+            Range::new("39 0 15"),
+        ]);
+        maps.push(map);
+        let almanac = Almanac::new(maps);
+
+        assert_eq!(almanac.convert(79), 81);
+        assert_eq!(almanac.convert(14), 53);
+        assert_eq!(almanac.convert(55), 57);
+        assert_eq!(almanac.convert(13), 52);
+    }
+
+    #[test]
+    fn test_map_convert_ranges() {
+        let map = Map::new(vec![Range::new("50 98 2"), Range::new("52 50 48")]);
+        let mut ranges = map.convert_ranges(&[45..55, 97..101, 10..10]);
+        ranges.sort_by_key(|range| range.start);
+        assert_eq!(ranges, vec![45..50, 50..52, 52..57, 99..100, 100..101]);
+    }
+
+    #[test]
+    fn test_map_invert() {
+        let map = Map::new(vec![Range::new("50 98 2"), Range::new("52 50 48")]);
+        assert_eq!(map.invert(50), vec![98]);
+        assert_eq!(map.invert(52), vec![50]);
+        assert_eq!(map.invert(49), vec![49]);
+        assert_eq!(map.invert(98), vec![96]);
+
+        // Both 10 and 20 convert to 10, and 20 is claimed by the first range
+        // before the second can move it to 30. Nothing converts to 22, as the
+        // first range moves it away.
+        let map = Map::new(vec![Range::new("10 20 5"), Range::new("30 20 5")]);
+        assert_eq!(map.invert(10), vec![10, 20]);
+        assert_eq!(map.invert(30), vec![30]);
+        assert_eq!(map.invert(22), vec![]);
+    }
+
+    #[test]
+    fn test_almanac_ranges_match_values() {
+        let maps = vec![
+            Map::new(vec![Range::new("50 98 2"), Range::new("52 50 48")]),
+            Map::new(vec![
+                Range::new("0 15 37"),
+                Range::new("37 52 2"),
+                Range::new("39 0 15"),
+            ]),
+        ];
+        let almanac = Almanac::new(maps);
+
+        let mut expected: Vec<u64> = (0..120).map(|seed| almanac.convert(seed)).collect();
+        expected.sort_unstable();
+        let mut converted: Vec<u64> = almanac
+            .convert_ranges(&[0..60, 60..120])
+            .into_iter()
+            .flatten()
+            .collect();
+        converted.sort_unstable();
+        assert_eq!(converted, expected);
+
+        for location in 0..120 {
+            for seed in almanac.invert(location) {
+                assert_eq!(almanac.convert(seed), location);
+            }
+        }
+        assert_eq!(almanac.invert(81), vec![79]);
+    }
+
+    #[test]
+    fn test_maps_chain() {
+        // The maps are out of order, and there's a shortcut from soil to
+        // water that a chain from seed to location doesn't need
+        let input = example!(
+            "
+            fertilizer-to-water map:
+            49 53 8
+            0 11 42
+
+            seed-to-soil map:
+            50 98 2
+            52 50 48
+
+            soil-to-water map:
+            0 0 1
+
+            soil-to-fertilizer map:
+            0 15 37
+            37 52 2
+            39 0 15
+
+            water-to-location map:
+            100 0 100
+            "
+        );
+        let maps = Maps::parse(sections(input)).unwrap();
+
+        let almanac = maps.chain("seed", "location").unwrap();
+        assert_eq!(almanac.maps.len(), 3);
+        assert_eq!(almanac.convert(79), 181);
+
+        let almanac = maps.chain("soil", "fertilizer").unwrap();
+        assert_eq!(almanac.convert(81), 81);
+        assert_eq!(almanac.convert(14), 53);
+
+        assert_eq!(maps.chain("seed", "seed").unwrap().convert(7), 7);
+        assert_eq!(
+            maps.chain("location", "seed").err(),
+            Some(AlmanacError::BrokenChain {
+                from: "location".to_string(),
+                to: "seed".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn test_maps_parse_errors() {
+        let input = "seeds: 1 2\n\nseed-to-soil map:\n50 98 2";
+        assert_eq!(
+            Maps::parse(sections(input)).err(),
+            Some(AlmanacError::BadHeader("seeds".to_string()))
+        );
+
+        let input = "seed-to-soil map:\n50 98 2\n\nseed-to-soil map:\n52 50 48";
+        assert_eq!(
+            Maps::parse(sections(input)).err(),
+            Some(AlmanacError::Duplicate {
+                source: "seed".to_string(),
+                destination: "soil".to_string()
+            })
+        );
+
+        let input = "seed-to-soil map:\n50 98 2\n52 x 48";
+        assert_eq!(
+            Maps::parse(sections(input)).err(),
+            Some(AlmanacError::BadRange {
+                line: "52 x 48".to_string(),
+                error: "52 x 48".parse::<Range>().unwrap_err(),
+            })
+        );
+        let input = "seed-to-soil map:\n50 98";
+        assert!(matches!(
+            Maps::parse(sections(input)),
+            Err(AlmanacError::BadRange { .. })
+        ));
+    }
+
+    #[test]
+    fn test_maps_validate() {
+        let input = example!(
+            "
+            seed-to-soil map:
+            0 10 10
+            50 15 10
+            90 40 5
+
+            soil-to-water map:
+            0 0 10
+            "
+        );
+        let maps = Maps::parse(sections(input)).unwrap();
+        let problems: Vec<String> = maps
+            .validate("seed", "location")
+            .iter()
+            .map(|problem| problem.to_string())
+            .collect();
+        assert_eq!(
+            problems,
+            [
+                "seed-to-soil map: source ranges 10..20 and 15..25 overlap",
+                "seed-to-soil map: no range covers the sources 25..40",
+                "no maps lead from seed to location",
+            ]
+        );
+        assert_eq!(maps.validate("seed", "water").len(), 2);
+    }
+}
@@ -0,0 +1,244 @@
+use std::fmt;
+
+use crate::{Almanac, Map};
+
+/// A run of numbers that a `PiecewiseMap` shifts by the same amount. It
+/// lasts until the next segment starts.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Segment {
+    pub start: u64,
+    pub offset: i64,
+}
+
+/// A conversion as a list of segments, sorted by where they start and
+/// covering every number from 0 up. Neighbouring segments never have the
+/// same offset, so two conversions that agree on every number have the same
+/// segments.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PiecewiseMap {
+    segments: Vec<Segment>,
+}
+
+impl PiecewiseMap {
+    /// The conversion that leaves every number alone.
+    pub fn identity() -> Self {
+        PiecewiseMap {
+            segments: vec![Segment {
+                start: 0,
+                offset: 0,
+            }],
+        }
+    }
+
+    // Builds the map from segments sorted by start, the first starting at 0,
+    // merging any that have the same offset as the one before
+    fn new(segments: impl IntoIterator<Item = Segment>) -> Self {
+        let mut merged: Vec<Segment> = Vec::new();
+        for segment in segments {
+            if merged
+                .last()
+                .is_none_or(|last| last.offset != segment.offset)
+            {
+                merged.push(segment);
+            }
+        }
+        PiecewiseMap { segments: merged }
+    }
+
+    pub fn segments(&self) -> &[Segment] {
+        &self.segments
+    }
+
+    // Finds the segment a number is in
+    fn position(&self, value: u64) -> usize {
+        self.segments
+            .partition_point(|segment| segment.start <= value)
+            - 1
+    }
+
+    // The first number after the segment at `index`, or None for the last
+    fn end(&self, index: usize) -> Option<u64> {
+        self.segments.get(index + 1).map(|segment| segment.start)
+    }
+
+    pub fn convert(&self, source: u64) -> u64 {
+        let segment = self.segments[self.position(source)];
+        source.wrapping_add_signed(segment.offset)
+    }
+
+    /// Returns the conversion that applies this one and then `next`.
+    pub fn then(&self, next: &PiecewiseMap) -> PiecewiseMap {
+        let mut segments: Vec<Segment> = Vec::new();
+        for (index, segment) in self.segments.iter().enumerate() {
+            let end = self.end(index);
+
+            // Where this segment's numbers land may cross several of the
+            // next map's segments, so split it wherever they do
+            let mut start = segment.start;
+            loop {
+                let position = next.position(start.wrapping_add_signed(segment.offset));
+                segments.push(Segment {
+                    start,
+                    offset: segment.offset + next.segments[position].offset,
+                });
+                let Some(split) = next
+                    .end(position)
+                    .and_then(|next_end| next_end.checked_add_signed(-segment.offset))
+                else {
+                    break;
+                };
+                if end.is_some_and(|end| split >= end) {
+                    break;
+                }
+                start = split;
+            }
+        }
+        PiecewiseMap::new(segments)
+    }
+}
+
+// Prints one row per segment, giving the first and last numbers in it and
+// how far it shifts them
+impl fmt::Display for PiecewiseMap {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let rows: Vec<[String; 3]> = self
+            .segments
+            .iter()
+            .enumerate()
+            .map(|(index, segment)| {
+                let last = self.end(index).map_or(u64::MAX, |end| end - 1);
+                [
+                    segment.start.to_string(),
+                    last.to_string(),
+                    format!("{:+}", segment.offset),
+                ]
+            })
+            .collect();
+        let width = |column: usize, header: &str| {
+            rows.iter()
+                .map(|row| row[column].len())
+                .chain([header.len()])
+                .max()
+                .unwrap()
+        };
+        let (from, to, offset) = (width(0, "from"), width(1, "to"), width(2, "offset"));
+
+        writeln!(f, "{:>from$}  {:>to$}  {:>offset$}", "from", "to", "offset")?;
+        for [start, last, shift] in &rows {
+            writeln!(f, "{start:>from$}  {last:>to$}  {shift:>offset$}")?;
+        }
+        Ok(())
+    }
+}
+
+impl Map {
+    /// Returns the map as a list of segments, which is quicker to look
+    /// numbers up in when there are many ranges.
+    pub fn to_piecewise(&self) -> PiecewiseMap {
+        // Between the edges of the ranges, every number is either in the
+        // same range or in none
+        let mut edges: Vec<u64> = vec![0];
+        for range in &self.ranges {
+            edges.push(range.source_start);
+            edges.push(range.source_start + range.length);
+        }
+        edges.sort_unstable();
+        edges.dedup();
+
+        PiecewiseMap::new(edges.into_iter().map(|start| Segment {
+            start,
+            offset: self.convert(start) as i64 - start as i64,
+        }))
+    }
+}
+
+impl Almanac {
+    /// Composes every map into one, which converts a number from the start
+    /// of the almanac to the end in a single lookup.
+    pub fn compose(&self) -> PiecewiseMap {
+        self.maps
+            .iter()
+            .fold(PiecewiseMap::identity(), |composed, map| {
+                composed.then(&map.to_piecewise())
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use aoc_utils::example;
+
+    use super::*;
+    use crate::Range;
+
+    fn segment(start: u64, offset: i64) -> Segment {
+        Segment { start, offset }
+    }
+
+    #[test]
+    fn test_to_piecewise() {
+        let map = Map::new(vec![Range::new("50 98 2"), Range::new("52 50 48")]);
+        let piecewise = map.to_piecewise();
+        assert_eq!(
+            piecewise.segments(),
+            [
+                segment(0, 0),
+                segment(50, 2),
+                segment(98, -48),
+                segment(100, 0)
+            ]
+        );
+        for source in 0..110 {
+            assert_eq!(piecewise.convert(source), map.convert(source));
+        }
+
+        // Ranges that line up with the same shift become one segment
+        let map = Map::new(vec![Range::new("15 10 5"), Range::new("20 15 5")]);
+        assert_eq!(
+            map.to_piecewise().segments(),
+            [segment(0, 0), segment(10, 5), segment(20, 0)]
+        );
+    }
+
+    #[test]
+    fn test_compose() {
+        let maps = vec![
+            Map::new(vec![Range::new("50 98 2"), Range::new("52 50 48")]),
+            Map::new(vec![
+                Range::new("0 15 37"),
+                Range::new("37 52 2"),
+                Range::new("39 0 15"),
+            ]),
+            Map::new(vec![Range::new("0 0 10")]),
+        ];
+        let almanac = Almanac::new(maps);
+        let composed = almanac.compose();
+        for source in 0..150 {
+            assert_eq!(composed.convert(source), almanac.convert(source));
+        }
+        assert_eq!(composed.convert(u64::MAX), u64::MAX);
+
+        assert_eq!(composed.segments()[0].start, 0);
+        assert!(composed
+            .segments()
+            .windows(2)
+            .all(|pair| { pair[0].start < pair[1].start && pair[0].offset != pair[1].offset }));
+
+        assert_eq!(Almanac::new(vec![]).compose(), PiecewiseMap::identity());
+    }
+
+    #[test]
+    fn test_display() {
+        let map = Map::new(vec![Range::new("50 98 2"), Range::new("52 50 48")]);
+        let table = example!(
+            "
+            from                    to  offset
+               0                    49      +0
+              50                    97      +2
+              98                    99     -48
+             100  18446744073709551615      +0
+            "
+        );
+        assert_eq!(map.to_piecewise().to_string(), table);
+    }
+}
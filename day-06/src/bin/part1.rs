@@ -1,5 +1,7 @@
 use std::iter::zip;
 
+use aoc_utils::ints_iter;
+
 fn main() {
     let input = include_str!("./input.txt");
     let output = process(input);
@@ -29,16 +31,9 @@ fn process(input: &str) -> String {
 }
 
 fn parse_races(input: &str) -> Vec<Race> {
-    let lines: Vec<&str> = input
-        .lines()
-        .map(|l| l.trim().split(':').last().unwrap().trim())
-        .collect();
-    let times = lines[0]
-        .split_ascii_whitespace()
-        .map(|num| num.parse::<u32>().unwrap());
-    let distances = lines[1]
-        .split_ascii_whitespace()
-        .map(|num| num.parse::<u32>().unwrap());
+    let mut lines = input.lines();
+    let times = ints_iter::<u32>(lines.next().unwrap());
+    let distances = ints_iter::<u32>(lines.next().unwrap());
     let races: Vec<Race> = zip(times, distances)
         .map(|(time, distance)| Race { time, distance })
         .collect();
@@ -47,6 +42,8 @@ fn parse_races(input: &str) -> Vec<Race> {
 
 #[cfg(test)]
 mod tests {
+    use aoc_utils::example;
+
     use super::*;
 
     #[test]
@@ -60,10 +57,12 @@ mod tests {
 
     #[test]
     fn test_process() {
-        let result = process(
-            "Time:      7  15   30
-             Distance:  9  40  200",
-        );
+        let result = process(example!(
+            "
+            Time:      7  15   30
+            Distance:  9  40  200
+            "
+        ));
         assert_eq!(result, "288".to_string());
     }
 }
@@ -1,3 +1,5 @@
+use aoc_utils::{beats_distance, ints_iter};
+
 fn main() {
     let input = include_str!("./input.txt");
     let output = process(input);
@@ -6,31 +8,17 @@ fn main() {
 
 #[derive(Clone, Copy, Debug)]
 struct Race {
-    time: f64,
-    distance: f64,
+    time: u64,
+    distance: u64,
 }
 
 impl Race {
     fn ways_to_win(&self) -> u64 {
-        // Use quadratic formula to calculate the upper and lower bounds for the winning hold times
-
-        let sqrt_discriminant = f64::sqrt((self.time * self.time) - 4.0 * (self.distance));
-
-        let upper = ((self.time) + sqrt_discriminant) / 2.0;
-        let upper = if f64::floor(upper) == upper {
-            (upper + 1.0) as u64
-        } else {
-            upper as u64
-        };
-
-        let lower = ((self.time) - sqrt_discriminant) / 2.0;
-        let lower = if f64::ceil(lower) == lower {
-            (lower - 1.0) as u64
-        } else {
-            f64::ceil(lower) as u64
-        };
-
-        upper - lower + 1
+        // Solve the quadratic exactly, since the concatenated race is too big
+        // for floating point to be trusted
+        beats_distance(self.time, self.distance).map_or(0, |hold_times| {
+            (hold_times.end() - hold_times.start() + 1) as u64
+        })
     }
 }
 
@@ -41,49 +29,53 @@ fn process(input: &str) -> String {
 }
 
 fn parse_race(input: &str) -> Race {
-    let lines: Vec<&str> = input
-        .lines()
-        .map(|l| l.trim().split(':').last().unwrap().trim())
-        .collect();
-    let time = lines[0]
-        .split_ascii_whitespace()
-        .fold(String::new(), |mut acc, num| {
-            acc.push_str(num);
-            acc
-        })
-        .parse::<f64>()
-        .unwrap();
-    let distance = lines[1]
-        .split_ascii_whitespace()
-        .fold(String::new(), |mut acc, num| {
-            acc.push_str(num);
-            acc
-        })
-        .parse::<f64>()
-        .unwrap();
+    // The spaces between the numbers are just bad kerning
+    let mut lines = input.lines().map(|line| line.replace(' ', ""));
+    let time = ints_iter::<u64>(&lines.next().unwrap()).next().unwrap();
+    let distance = ints_iter::<u64>(&lines.next().unwrap()).next().unwrap();
 
     Race { time, distance }
 }
 
 #[cfg(test)]
 mod tests {
+    use aoc_utils::example;
+
     use super::*;
 
     #[test]
     fn test_ways_to_win() {
         let race = Race {
-            time: 15.0,
-            distance: 40.0,
+            time: 15,
+            distance: 40,
         };
         assert_eq!(race.ways_to_win(), 8);
+
+        let race = Race {
+            time: 30,
+            distance: 200,
+        };
+        assert_eq!(race.ways_to_win(), 9);
+    }
+
+    #[test]
+    fn test_ways_to_win_large() {
+        // Too big for an f64 to hold the discriminant exactly
+        let race = Race {
+            time: 1 << 33,
+            distance: u64::MAX,
+        };
+        assert_eq!(race.ways_to_win(), 1);
     }
 
     #[test]
     fn test_process() {
-        let result = process(
-            "Time:      7  15   30
-             Distance:  9  40  200",
-        );
+        let result = process(example!(
+            "
+            Time:      7  15   30
+            Distance:  9  40  200
+            "
+        ));
         assert_eq!(result, "71503".to_string());
     }
 }
@@ -1,6 +1,11 @@
+use std::collections::BTreeMap;
+use std::io::BufRead;
+
+use aoc_utils::{each_line, run, Counter};
+use day_07::Bids;
+
 fn main() {
-    let input = include_str!("./input.txt");
-    let output = process(input);
+    let output = run(include_str!("./input.txt"), process_reader);
     dbg!(output);
 }
 
@@ -55,92 +60,71 @@ enum Type {
 
 impl Type {
     fn new(input: &str) -> Self {
-        // Create a map from cards to their frequencies.
-        // We replace the actual values of the cards with
-        // the value of the corresponding index into the
-        // array of frequencies.
-        //
-        // The only reason we sort the cards is to group
-        // cards with the same value together. This makes
-        // it easy to replace them with the same index value.
-        let mut cards = input.as_bytes().to_owned();
-        cards.sort();
-        let mut current = cards[0];
-        let mut index = 0;
-        for i in 0..cards.len() {
-            if cards[i] != current {
-                current = cards[i];
-                index += 1;
-            }
-            cards[i] = index
-        }
-        let mut counts = [0, 0, 0, 0, 0];
-        for i in 0..counts.len() {
-            counts[cards[i] as usize] += 1;
-        }
+        let counts: Counter<char> = input.chars().collect();
+        Type::from_counts(&counts.signature())
+    }
 
-        // Use the card frequencies to find the hand's type
-        counts.sort();
+    // Find the hand's type from the card counts, largest first
+    fn from_counts(counts: &[usize]) -> Self {
         match counts {
-            [0, 0, 0, 0, 5] => Type::FiveOfAKind,
-            [0, 0, 0, 1, 4] => Type::FourOfAKind,
-            [0, 0, 0, 2, 3] => Type::FullHouse,
-            [0, 0, 1, 1, 3] => Type::ThreeOfAKind,
-            [0, 0, 1, 2, 2] => Type::TwoPair,
-            [0, 1, 1, 1, 2] => Type::OnePair,
-            [1, 1, 1, 1, 1] => Type::HighCard,
-            _ => panic!("bad input: {input}, cards: {:#?}", cards),
+            [n, ..] if *n >= 5 => Type::FiveOfAKind,
+            [4, ..] => Type::FourOfAKind,
+            [3, 2, ..] => Type::FullHouse,
+            [3, ..] => Type::ThreeOfAKind,
+            [2, 2, ..] => Type::TwoPair,
+            [2, ..] => Type::OnePair,
+            _ => Type::HighCard,
         }
     }
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
-struct Hand(Type, Card, Card, Card, Card, Card);
+// Hands are compared by type and then card by card, so they can have any
+// number of cards
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+struct Hand(Type, Vec<Card>);
 
 impl Hand {
     fn new(input: &str) -> Self {
-        let mut cards = input.chars().map(|c| Card::new(c));
-        Hand(
-            Type::new(input),
-            cards.next().unwrap(),
-            cards.next().unwrap(),
-            cards.next().unwrap(),
-            cards.next().unwrap(),
-            cards.next().unwrap(),
-        )
+        let cards = input.chars().map(|c| Card::new(c)).collect();
+        Hand(Type::new(input), cards)
     }
 }
 
-fn process(input: &str) -> String {
-    let mut hands: Vec<(Hand, u32)> = Vec::new();
-    for line in input.lines() {
+fn process_reader(reader: impl BufRead) -> String {
+    let mut hands: BTreeMap<Hand, Bids> = BTreeMap::new();
+    each_line(reader, |line| {
         let tmp: Vec<&str> = line.split_ascii_whitespace().collect();
         let hand = Hand::new(tmp[0]);
-        let bid: u32 = tmp[1].parse().unwrap();
-        hands.push((hand, bid));
-    }
-
-    // Reverse sort the hands
-    hands.sort_by(|hand1, hand2| hand2.0.cmp(&hand1.0));
-
-    // Calculate the winnings
-    let mut winnings: u32 = 0;
-    for rank in 0..hands.len() {
-        winnings += hands[rank].1 * (rank + 1) as u32;
+        let bid: u64 = tmp[1].parse().unwrap();
+        hands.entry(hand).or_default().add(bid);
+    });
+
+    // Calculate the winnings, starting from the weakest hand
+    let mut winnings: u64 = 0;
+    let mut rank: u64 = 1;
+    for bids in hands.values().rev() {
+        winnings += rank * bids.sum + bids.weighted_sum;
+        rank += bids.count;
     }
     winnings.to_string()
 }
 
 #[cfg(test)]
 mod tests {
+    use aoc_utils::example;
+
     use super::Card::*;
     use super::Type::*;
     use super::*;
 
+    fn process(input: &str) -> String {
+        process_reader(input.as_bytes())
+    }
+
     #[test]
     fn test_new_hand() {
         let hand = Hand::new("32T3K");
-        assert_eq!(hand, Hand(OnePair, Three, Two, Ten, Three, King))
+        assert_eq!(hand, Hand(OnePair, vec![Three, Two, Ten, Three, King]))
     }
 
     #[test]
@@ -167,15 +151,48 @@ mod tests {
         assert_eq!(Type::new(hand), HighCard);
     }
 
+    #[test]
+    fn test_other_hand_sizes() {
+        assert_eq!(Type::new("AAA"), ThreeOfAKind);
+        assert_eq!(Type::new("A2A2"), TwoPair);
+        assert_eq!(Type::new("AAKKKQ"), FullHouse);
+        assert_eq!(Type::new("AAAAAAK"), FiveOfAKind);
+
+        assert_eq!(Hand::new("K2K"), Hand(OnePair, vec![King, Two, King]));
+        let result = process(example!(
+            "
+            AAA 1
+            2233 2
+            AK 3
+            "
+        ));
+        assert_eq!(result, (3 * 1 + 2 * 2 + 1 * 3).to_string());
+    }
+
     #[test]
     fn test_process() {
-        let result = process(
-            "32T3K 765
-             T55J5 684
-             KK677 28
-             KTJJT 220
-             QQQJA 483",
-        );
+        let result = process(example!(
+            "
+            32T3K 765
+            T55J5 684
+            KK677 28
+            KTJJT 220
+            QQQJA 483
+            "
+        ));
         assert_eq!(result, "6440".to_string());
     }
+
+    #[test]
+    fn test_equal_hands() {
+        // Equal hands are ranked in the order they were dealt
+        let result = process(example!(
+            "
+            AAAAA 1
+            23456 2
+            AAAAA 3
+            "
+        ));
+        assert_eq!(result, "13".to_string());
+    }
 }
@@ -1,6 +1,11 @@
+use std::collections::BTreeMap;
+use std::io::BufRead;
+
+use aoc_utils::{each_line, run, Counter};
+use day_07::Bids;
+
 fn main() {
-    let input = include_str!("./input.txt");
-    let output = process(input);
+    let output = run(include_str!("./input.txt"), process_reader);
     dbg!(output);
 }
 
@@ -55,116 +60,83 @@ enum Type {
 
 impl Type {
     fn new(input: &str) -> Self {
-        let mut cards = input.as_bytes().to_owned();
-        let joker_count = cards.iter().filter(|card| **card as char == 'J').count();
-
-        // Create an array to count the cards of each kind. We first replace the
-        // actual values of the cards with the value of the corresponding index
-        // into the array of counts.
-        //
-        // We sort the cards to group cards with the same value together. This
-        // makes it easy to replace them with the same index value.
-        //
-        // The index used for Jokers is 0, and their counts are not actually
-        // added to the mapping. Instead the number of Jokers is added to the
-        // highest count.
-
-        // Replace each card with the index at which that card's count
-        // should be stored
-        cards.sort();
-        let mut current = cards[0];
-        let mut index = 1;
-        for i in 0..cards.len() {
-            if cards[i] == 'J' as u8 {
-                cards[i] = 0;
-                continue;
-            }
-            if cards[i] != current {
-                current = cards[i];
-                index += 1;
-            }
-            cards[i] = index
+        let mut counts: Counter<char> = input.chars().collect();
+
+        // Jokers always do best by pretending to be whichever card is already
+        // the most common, so add them to the highest count
+        let joker_count = counts.remove(&'J');
+        let mut counts = counts.signature();
+        if counts.is_empty() {
+            counts.push(0);
         }
+        counts[0] += joker_count;
 
-        // Calculate the count of each non-Joker card
-        let mut counts = [0, 0, 0, 0, 0];
-        for i in 0..counts.len() {
-            // Skip calculating the Joker count
-            if cards[i] != 0 {
-                // If there were no Jokers, the index in 'cards' is too big
-                let index = if joker_count == 0 {
-                    cards[i] - 1
-                } else {
-                    cards[i]
-                };
-                counts[index as usize] += 1;
-            }
-        }
+        Type::from_counts(&counts)
+    }
 
-        // Use the card counts to find the hand's type
-        counts.sort();
-        counts[4] += joker_count;
+    // Find the hand's type from the card counts, largest first
+    fn from_counts(counts: &[usize]) -> Self {
         match counts {
-            [0, 0, 0, 0, 5] => Type::FiveOfAKind,
-            [0, 0, 0, 1, 4] => Type::FourOfAKind,
-            [0, 0, 0, 2, 3] => Type::FullHouse,
-            [0, 0, 1, 1, 3] => Type::ThreeOfAKind,
-            [0, 0, 1, 2, 2] => Type::TwoPair,
-            [0, 1, 1, 1, 2] => Type::OnePair,
-            [1, 1, 1, 1, 1] => Type::HighCard,
-            _ => panic!("bad input: {input}, cards: {:#?}", cards),
+            [n, ..] if *n >= 5 => Type::FiveOfAKind,
+            [4, ..] => Type::FourOfAKind,
+            [3, 2, ..] => Type::FullHouse,
+            [3, ..] => Type::ThreeOfAKind,
+            [2, 2, ..] => Type::TwoPair,
+            [2, ..] => Type::OnePair,
+            _ => Type::HighCard,
         }
     }
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
-struct Hand(Type, Card, Card, Card, Card, Card);
+// Hands are compared by type and then card by card, so they can have any
+// number of cards
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+struct Hand(Type, Vec<Card>);
 
 impl Hand {
     fn new(input: &str) -> Self {
-        let mut cards = input.chars().map(|c| Card::new(c));
-        Hand(
-            Type::new(input),
-            cards.next().unwrap(),
-            cards.next().unwrap(),
-            cards.next().unwrap(),
-            cards.next().unwrap(),
-            cards.next().unwrap(),
-        )
+        let cards = input.chars().map(|c| Card::new(c)).collect();
+        Hand(Type::new(input), cards)
     }
 }
 
-fn process(input: &str) -> String {
-    // Create the list of hands by parsing the input
-    let mut hands: Vec<(Hand, u32)> = Vec::new();
-    for line in input.lines() {
+fn process_reader(reader: impl BufRead) -> String {
+    // Group the bids by hand while parsing the input
+    let mut hands: BTreeMap<Hand, Bids> = BTreeMap::new();
+    each_line(reader, |line| {
         let tmp: Vec<&str> = line.split_ascii_whitespace().collect();
         let hand = Hand::new(tmp[0]);
-        let bid: u32 = tmp[1].parse().unwrap();
-        hands.push((hand, bid));
-    }
-
-    // Reverse sort the hands so the lowest rank is first on the list
-    hands.sort_by(|hand1, hand2| hand2.0.cmp(&hand1.0));
-
-    // Calculate the winnings
-    let mut winnings: u32 = 0;
-    for rank in 0..hands.len() {
-        winnings += hands[rank].1 * (rank + 1) as u32;
+        let bid: u64 = tmp[1].parse().unwrap();
+        hands.entry(hand).or_default().add(bid);
+    });
+
+    // Calculate the winnings, starting from the weakest hand, which has the
+    // lowest rank
+    let mut winnings: u64 = 0;
+    let mut rank: u64 = 1;
+    for bids in hands.values().rev() {
+        winnings += rank * bids.sum + bids.weighted_sum;
+        rank += bids.count;
     }
     winnings.to_string()
 }
 
 #[cfg(test)]
 mod tests {
+    use aoc_utils::example;
+
     use super::Card::*;
     use super::Type::*;
     use super::*;
 
+    fn process(input: &str) -> String {
+        process_reader(input.as_bytes())
+    }
+
     #[test]
     fn test_new_hand() {
         let hand = Hand::new("32T3K");
-        assert_eq!(hand, Hand(OnePair, Three, Two, Ten, Three, King))
+        assert_eq!(hand, Hand(OnePair, vec![Three, Two, Ten, Three, King]))
     }
 
     #[test]
@@ -194,27 +166,32 @@ mod tests {
     #[test]
     fn test_jokers() {
         let hand = Hand::new("KTJJT");
-        assert_eq!(hand, Hand(FourOfAKind, King, Ten, Joker, Joker, Ten));
+        assert_eq!(hand, Hand(FourOfAKind, vec![King, Ten, Joker, Joker, Ten]));
 
         let hand = Hand::new("KK677");
-        assert_eq!(hand, Hand(TwoPair, King, King, Six, Seven, Seven));
+        assert_eq!(hand, Hand(TwoPair, vec![King, King, Six, Seven, Seven]));
 
         let hand = Hand::new("JJJJJ");
-        assert_eq!(hand, Hand(FiveOfAKind, Joker, Joker, Joker, Joker, Joker));
+        assert_eq!(
+            hand,
+            Hand(FiveOfAKind, vec![Joker, Joker, Joker, Joker, Joker])
+        );
 
         let hand = Hand::new("AKQT9");
-        assert_eq!(hand, Hand(HighCard, Ace, King, Queen, Ten, Nine));
+        assert_eq!(hand, Hand(HighCard, vec![Ace, King, Queen, Ten, Nine]));
     }
 
     #[test]
     fn test_process() {
-        let result = process(
-            "32T3K 765
-             T55J5 684
-             KK677 28
-             KTJJT 220
-             QQQJA 483",
-        );
+        let result = process(example!(
+            "
+            32T3K 765
+            T55J5 684
+            KK677 28
+            KTJJT 220
+            QQQJA 483
+            "
+        ));
         assert_eq!(result, "5905".to_string());
     }
 }
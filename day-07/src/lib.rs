@@ -0,0 +1,34 @@
+// Equal hands keep their input order when ranked, so they take consecutive
+// ranks in the order their bids were read. That means each distinct hand only
+// needs how many times it was dealt, the sum of its bids, and the sum of each
+// bid times the number of equal hands before it.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Bids {
+    pub count: u64,
+    pub sum: u64,
+    pub weighted_sum: u64,
+}
+
+impl Bids {
+    pub fn add(&mut self, bid: u64) {
+        self.weighted_sum += bid * self.count;
+        self.sum += bid;
+        self.count += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_add() {
+        let mut bids = Bids::default();
+        for bid in [5, 7, 11] {
+            bids.add(bid);
+        }
+        assert_eq!(bids.count, 3);
+        assert_eq!(bids.sum, 23);
+        assert_eq!(bids.weighted_sum, 7 + 2 * 11);
+    }
+}
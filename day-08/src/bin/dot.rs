@@ -0,0 +1,18 @@
+use std::env;
+
+use day_08::Network;
+
+// Prints the network as a Graphviz graph, boxing up each group of connected
+// nodes when given --clusters:
+//
+//     cargo run --bin dot -- --clusters | dot -Tsvg > network.svg
+fn main() {
+    let clusters = match env::args().nth(1).as_deref() {
+        Some("--clusters") => true,
+        Some(arg) => panic!("unknown argument: {arg}"),
+        None => false,
+    };
+    let lines = include_str!("./input.txt").lines().skip(2);
+    let network = Network::new(lines).unwrap_or_else(|error| panic!("{error}"));
+    print!("{}", network.to_dot(clusters));
+}
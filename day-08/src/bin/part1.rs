@@ -1,6 +1,8 @@
-use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+use std::mem;
 
-use aoc_utils::Tokenizer;
+use day_08::{Direction, Network, UndefinedNode};
 
 fn main() {
     let input = include_str!("./input.txt");
@@ -8,12 +10,6 @@ fn main() {
     dbg!(output);
 }
 
-#[derive(Clone, Copy, Debug, PartialEq)]
-enum Direction {
-    Left,
-    Right,
-}
-
 struct Directions {
     directions: Vec<Direction>,
     index: usize,
@@ -50,60 +46,106 @@ impl Iterator for Directions {
     }
 }
 
-struct Node<'a> {
-    name: &'a str,
-    left: &'a str,
-    right: &'a str,
+/// Why following the directions doesn't get to the target.
+#[derive(Debug, PartialEq)]
+enum WalkError {
+    /// The network has no node with this name
+    MissingNode(String),
+    /// A node, such as the start or the target, is named as a neighbour
+    /// but has no line of its own
+    UndefinedNode(String),
+    /// No path through the network leads to the target
+    Unreachable,
+    /// The walk got back to a node at the same place in the directions
+    /// without passing the target, so it would go round forever
+    Cycle { steps: u32 },
 }
 
-fn process(input: &str) -> String {
-    use Direction::*;
-
-    let mut lines = input.lines();
+impl fmt::Display for WalkError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WalkError::MissingNode(name) => write!(f, "no node named {name}"),
+            WalkError::UndefinedNode(name) => {
+                write!(f, "node {name} is a neighbour but is never defined")
+            }
+            WalkError::Unreachable => write!(f, "target never reached: no path leads to it"),
+            WalkError::Cycle { steps } => {
+                write!(
+                    f,
+                    "target never reached: the walk repeats after {steps} steps"
+                )
+            }
+        }
+    }
+}
 
-    let mut directions = lines
-        .next()
-        .map(|l| l.trim())
-        .map(|l| Directions::new(l))
-        .unwrap();
+impl Error for WalkError {}
 
-    lines.next();
+impl From<UndefinedNode> for WalkError {
+    fn from(error: UndefinedNode) -> Self {
+        WalkError::UndefinedNode(error.0)
+    }
+}
 
-    let mut nodes: HashMap<&str, Node> = HashMap::new();
-    for line in lines {
-        let node = parse_node(line.trim());
-        nodes.insert(node.name, node);
+// Counts the steps from `start` to `end` following the directions
+fn walk(
+    network: &Network,
+    directions: &mut Directions,
+    start: &str,
+    end: &str,
+) -> Result<u32, WalkError> {
+    let id = |name: &str| {
+        network
+            .names
+            .get(name)
+            .ok_or_else(|| WalkError::MissingNode(name.to_string()))
+    };
+    let mut node = id(start)?;
+    let end = id(end)?;
+    if !network.reachable(node, end) {
+        return Err(WalkError::Unreachable);
     }
 
+    // Where the walk goes only depends on the node and the place in the
+    // directions, so seeing both again means it's going round in circles
+    let length = directions.directions.len();
+    let mut seen = vec![false; network.nodes.len() * length];
     let mut count: u32 = 0;
-    let mut node = &nodes["AAA"];
-    while node.name != "ZZZ" {
-        node = match directions.next().unwrap() {
-            Left => &nodes[node.left],
-            Right => &nodes[node.right],
-        };
+    while node != end {
+        let state = node as usize * length + directions.index;
+        if mem::replace(&mut seen[state], true) {
+            return Err(WalkError::Cycle { steps: count });
+        }
+        node = network.next(node, directions.next().unwrap());
         count += 1;
     }
+    Ok(count)
+}
 
+fn process(input: &str) -> String {
+    let count = count_steps(input).unwrap_or_else(|error| panic!("{error}"));
     count.to_string()
 }
 
-fn parse_node(line: &str) -> Node {
-    let mut tokens = Tokenizer::new(line);
+// Counts the steps from AAA to ZZZ. Network::new checks that every node it
+// knows has a line of its own, so once it's built, the start and the target
+// are real nodes if they have ids at all.
+fn count_steps(input: &str) -> Result<u32, WalkError> {
+    let mut lines = input.lines();
+
+    let mut directions = lines.next().map(Directions::new).unwrap();
+
+    lines.next();
 
-    let name = tokens.get();
-    tokens.consume("=");
-    tokens.consume("(");
-    let left = tokens.get();
-    tokens.consume(",");
-    let right = tokens.get();
-    tokens.consume(")");
+    let network = Network::new(lines)?;
 
-    Node { name, left, right }
+    walk(&network, &mut directions, "AAA", "ZZZ")
 }
 
 #[cfg(test)]
 mod tests {
+    use aoc_utils::example;
+
     use super::*;
     use Direction::*;
 
@@ -118,36 +160,97 @@ mod tests {
     }
 
     #[test]
-    fn test_parse_node() {
-        let line = "AAA = (BBB, CCC)";
-        let value = parse_node(line);
-        assert_eq!(value.name, "AAA");
-        assert_eq!(value.left, "BBB");
-        assert_eq!(value.right, "CCC");
+    fn test_process() {
+        let result = process(example!(
+            "
+                RL
+
+                AAA = (BBB, CCC)
+                BBB = (DDD, EEE)
+                CCC = (ZZZ, GGG)
+                DDD = (DDD, DDD)
+                EEE = (EEE, EEE)
+                GGG = (GGG, GGG)
+                ZZZ = (ZZZ, ZZZ)
+                "
+        ));
+        assert_eq!(result, "2".to_string());
+
+        let result = process(example!(
+            "
+                LLR
+
+                AAA = (BBB, BBB)
+                BBB = (AAA, ZZZ)
+                ZZZ = (ZZZ, ZZZ)
+                "
+        ));
+        assert_eq!(result, "6".to_string());
     }
 
     #[test]
-    fn test_process() {
-        let result = process(
-            "RL
-
-             AAA = (BBB, CCC)
-             BBB = (DDD, EEE)
-             CCC = (ZZZ, GGG)
-             DDD = (DDD, DDD)
-             EEE = (EEE, EEE)
-             GGG = (GGG, GGG)
-             ZZZ = (ZZZ, ZZZ)",
+    fn test_never_reached() {
+        let input = example!(
+            "
+            AAA = (BBB, BBB)
+            BBB = (AAA, AAA)
+            ZZZ = (ZZZ, ZZZ)
+            "
         );
-        assert_eq!(result, "2".to_string());
+        let network = Network::new(input.lines()).unwrap();
+        let mut directions = Directions::new("L");
+        assert_eq!(
+            walk(&network, &mut directions, "AAA", "ZZZ"),
+            Err(WalkError::Unreachable)
+        );
+        assert_eq!(
+            walk(&network, &mut directions, "AAA", "YYY"),
+            Err(WalkError::MissingNode("YYY".to_string()))
+        );
+
+        // ZZZ is only ever a right turn away, but the directions only go left
+        let input = example!(
+            "
+            AAA = (BBB, ZZZ)
+            BBB = (AAA, ZZZ)
+            ZZZ = (ZZZ, ZZZ)
+            "
+        );
+        let network = Network::new(input.lines()).unwrap();
+        let mut directions = Directions::new("LLL");
+        assert_eq!(
+            walk(&network, &mut directions, "AAA", "ZZZ"),
+            Err(WalkError::Cycle { steps: 6 })
+        );
+    }
 
-        let result = process(
-            "LLR
+    #[test]
+    fn test_undefined_target() {
+        // ZZZ is one of BBB's neighbours, but has no line of its own
+        let input = example!(
+            "
+            LR
 
-             AAA = (BBB, BBB)
-             BBB = (AAA, ZZZ)
-             ZZZ = (ZZZ, ZZZ)",
+            AAA = (BBB, BBB)
+            BBB = (AAA, ZZZ)
+            "
+        );
+        assert_eq!(
+            count_steps(input),
+            Err(WalkError::UndefinedNode("ZZZ".to_string()))
+        );
+
+        let input = example!(
+            "
+            LR
+
+            BBB = (AAA, ZZZ)
+            ZZZ = (ZZZ, ZZZ)
+            "
+        );
+        assert_eq!(
+            count_steps(input),
+            Err(WalkError::UndefinedNode("AAA".to_string()))
         );
-        assert_eq!(result, "6".to_string());
     }
 }
@@ -1,6 +1,4 @@
-use std::collections::HashMap;
-
-use aoc_utils::Tokenizer;
+use day_08::{Direction, Network};
 
 fn main() {
     let input = include_str!("./input.txt");
@@ -8,12 +6,6 @@ fn main() {
     dbg!(output);
 }
 
-#[derive(Clone, Copy, Debug, PartialEq)]
-enum Direction {
-    Left,
-    Right,
-}
-
 #[derive(Debug)]
 struct Directions {
     directions: Vec<Direction>,
@@ -35,7 +27,7 @@ impl Directions {
         }
     }
 
-    fn iter<'a>(&'a self) -> DirectionsIterator<'a> {
+    fn iter(&self) -> DirectionsIterator<'_> {
         DirectionsIterator {
             directions: &self.directions,
             index: 0,
@@ -44,11 +36,11 @@ impl Directions {
 }
 
 struct DirectionsIterator<'a> {
-    directions: &'a Vec<Direction>,
+    directions: &'a [Direction],
     index: usize,
 }
 
-impl<'a> Iterator for DirectionsIterator<'a> {
+impl Iterator for DirectionsIterator<'_> {
     type Item = Direction;
 
     fn next(&mut self) -> Option<Self::Item> {
@@ -61,47 +53,146 @@ impl<'a> Iterator for DirectionsIterator<'a> {
     }
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
-struct Node<'a> {
-    name: &'a str,
-    left: &'a str,
-    right: &'a str,
+// The steps at which a ghost is on an end node. Where a ghost goes next only
+// depends on its node and how far it is through the directions, so once one
+// of those states comes round again its walk repeats. The hits before that
+// cycle starts happen once, and the hits in it come round every `period`
+// steps.
+#[derive(Debug, PartialEq)]
+struct Ghost {
+    // Hits before the cycle starts
+    prefix: Vec<u64>,
+    // The step the cycle starts at
+    cycle_start: u64,
+    // Hits the first time round the cycle
+    cycle: Vec<u64>,
+    period: u64,
 }
 
-impl<'a> Node<'a> {
-    fn next(&self, direction: Direction, node_map: &HashMap<&str, Node<'a>>) -> Self {
-        use Direction::*;
-        match direction {
-            Left => node_map[self.left],
-            Right => node_map[self.right],
+impl Ghost {
+    fn new(start: u32, directions: &Directions, network: &Network) -> Self {
+        let length = directions.directions.len();
+        // The step each state was first seen at, indexed by node id and
+        // place in the directions
+        let mut seen: Vec<Option<u64>> = vec![None; network.nodes.len() * length];
+        let mut hits: Vec<u64> = Vec::new();
+        let mut steps: u64 = 0;
+        let mut current = start;
+        let mut directions = directions.iter();
+        let cycle_start = loop {
+            let state = current as usize * length + (steps % length as u64) as usize;
+            if let Some(first) = seen[state] {
+                break first;
+            }
+            seen[state] = Some(steps);
+            if network.is_end(current) {
+                hits.push(steps);
+            }
+            let direction = directions.next().unwrap();
+            current = network.next(current, direction);
+            steps += 1;
+        };
+        let (prefix, cycle) = hits.into_iter().partition(|&hit| hit < cycle_start);
+        Ghost {
+            prefix,
+            cycle_start,
+            cycle,
+            period: steps - cycle_start,
+        }
+    }
+
+    fn hits(&self, steps: u64) -> bool {
+        if steps < self.cycle_start {
+            self.prefix.contains(&steps)
+        } else {
+            let offset = self.cycle_start + (steps - self.cycle_start) % self.period;
+            self.cycle.contains(&offset)
         }
     }
+
+    // The period, if the ghost is on an end node after exactly every
+    // multiple of it and at no other time
+    fn aligned_period(&self) -> Option<u64> {
+        (self.prefix.is_empty() && self.cycle == [self.period]).then_some(self.period)
+    }
 }
 
-fn start_nodes<'a>(node_map: &HashMap<&str, Node<'a>>) -> Vec<Node<'a>> {
-    let mut nodes = Vec::new();
-    node_map.keys().for_each(|key| {
-        if key.ends_with("A") {
-            nodes.push(node_map[key]);
+// Finds the first step at which every ghost is on an end node, if there is one
+fn first_common_end(ghosts: &[Ghost]) -> Option<u64> {
+    // The puzzle inputs are built so that this holds, which makes the answer
+    // the least common multiple of the periods
+    if let Some(periods) = ghosts
+        .iter()
+        .map(Ghost::aligned_period)
+        .collect::<Option<Vec<u64>>>()
+    {
+        return periods.into_iter().reduce(lcm);
+    }
+
+    // Until the last ghost to reach its cycle gets there, the ghosts can
+    // only meet on one of the hits before its cycle
+    let last = ghosts.iter().max_by_key(|ghost| ghost.cycle_start)?;
+    if let Some(&steps) = last
+        .prefix
+        .iter()
+        .find(|&&steps| ghosts.iter().all(|ghost| ghost.hits(steps)))
+    {
+        return Some(steps);
+    }
+
+    // After that, every ghost is in its cycle, so combine the steps each one
+    // can be on an end node at into the steps they all can
+    let mut residues: Vec<u64> = vec![0];
+    let mut modulus: u64 = 1;
+    for ghost in ghosts {
+        let mut combined: Vec<u64> = Vec::new();
+        for &residue in &residues {
+            for &hit in &ghost.cycle {
+                if let Some((residue, _)) = crt(residue, modulus, hit, ghost.period) {
+                    combined.push(residue);
+                }
+            }
         }
-    });
-    nodes
+        combined.sort_unstable();
+        combined.dedup();
+        residues = combined;
+        modulus = lcm(modulus, ghost.period);
+    }
+
+    let start = last.cycle_start;
+    residues
+        .into_iter()
+        .map(|residue| start + (residue + modulus - start % modulus) % modulus)
+        .min()
+}
+
+// Solves x = a (mod n) and x = b (mod m), where the moduli needn't be
+// coprime, returning x and the modulus it's unique to
+fn crt(a: u64, n: u64, b: u64, m: u64) -> Option<(u64, u64)> {
+    let g = gcd(n, m) as i128;
+    let (a, n, b, m) = (a as i128, n as i128, b as i128, m as i128);
+    let difference = b - a;
+    if difference % g != 0 {
+        return None;
+    }
+
+    // Find k with a + n * k = b (mod m)
+    let m = m / g;
+    let k = (difference / g).rem_euclid(m) * inverse(n / g, m) % m;
+    let modulus = n * m;
+    Some(((a + n * k).rem_euclid(modulus) as u64, modulus as u64))
 }
 
-fn detect_cycle<'a>(
-    start: Node<'a>,
-    directions: &Directions,
-    node_map: &'a HashMap<&'a str, Node<'a>>,
-) -> Vec<Node<'a>> {
-    let mut nodes: Vec<Node> = Vec::new();
-    let mut current = start;
-    let mut directions = directions.iter();
-    while !current.name.ends_with("Z") {
-        nodes.push(current);
-        let direction = directions.next().unwrap();
-        current = current.next(direction, node_map);
-    }
-    nodes
+// The inverse of a modulo m, for coprime a and m
+fn inverse(a: i128, m: i128) -> i128 {
+    let (mut old_r, mut r) = (a.rem_euclid(m), m);
+    let (mut old_s, mut s) = (1, 0);
+    while r != 0 {
+        let quotient = old_r / r;
+        (old_r, r) = (r, old_r - quotient * r);
+        (old_s, s) = (s, old_s - quotient * s);
+    }
+    old_s.rem_euclid(m)
 }
 
 fn lcm(first: u64, second: u64) -> u64 {
@@ -128,51 +219,26 @@ fn gcd(first: u64, second: u64) -> u64 {
 fn process(input: &str) -> String {
     let mut lines = input.lines();
 
-    let directions = lines
-        .next()
-        .map(|l| l.trim())
-        .map(|l| Directions::new(l))
-        .unwrap();
+    let directions = lines.next().map(Directions::new).unwrap();
 
     lines.next();
 
-    let mut node_map: HashMap<&str, Node> = HashMap::new();
-    for line in lines {
-        let node = parse_node(line.trim());
-        node_map.insert(node.name, node);
-    }
+    let network = Network::new(lines).unwrap_or_else(|error| panic!("{error}"));
 
-    let mut steps: Vec<u64> = Vec::new();
-    let start_nodes = start_nodes(&node_map);
-    for node in start_nodes.iter() {
-        let cycle = detect_cycle(*node, &directions, &node_map);
-        steps.push(cycle.len() as u64);
+    let mut ghosts: Vec<Ghost> = Vec::new();
+    for node in network.start_nodes() {
+        ghosts.push(Ghost::new(node, &directions, &network));
     }
 
-    let output = steps
-        .iter()
-        .map(|e| *e)
-        .reduce(|acc, e| lcm(acc, e))
-        .unwrap();
+    let output = first_common_end(&ghosts)
+        .unwrap_or_else(|| panic!("the ghosts are never all on end nodes at once"));
     output.to_string()
 }
 
-fn parse_node(line: &str) -> Node {
-    let mut tokens = Tokenizer::new(line);
-
-    let name = tokens.get();
-    tokens.consume("=");
-    tokens.consume("(");
-    let left = tokens.get();
-    tokens.consume(",");
-    let right = tokens.get();
-    tokens.consume(")");
-
-    Node { name, left, right }
-}
-
 #[cfg(test)]
 mod tests {
+    use aoc_utils::example;
+
     use super::*;
     use Direction::*;
 
@@ -188,28 +254,112 @@ mod tests {
     }
 
     #[test]
-    fn test_parse_node() {
-        let line = "AAA = (BBB, CCC)";
-        let value = parse_node(line);
-        assert_eq!(value.name, "AAA");
-        assert_eq!(value.left, "BBB");
-        assert_eq!(value.right, "CCC");
+    fn test_process() {
+        let result = process(example!(
+            "
+                LR
+
+                11A = (11B, XXX)
+                11B = (XXX, 11Z)
+                11Z = (11B, XXX)
+                22A = (22B, XXX)
+                22B = (22C, 22C)
+                22C = (22Z, 22Z)
+                22Z = (22B, 22B)
+                XXX = (XXX, XXX)
+                "
+        ));
+        assert_eq!(result, "6".to_string());
     }
 
     #[test]
-    fn test_process() {
-        let result = process(
-            "LR
-
-             11A = (11B, XXX)
-             11B = (XXX, 11Z)
-             11Z = (11B, XXX)
-             22A = (22B, XXX)
-             22B = (22C, 22C)
-             22C = (22Z, 22Z)
-             22Z = (22B, 22B)
-             XXX = (XXX, XXX)",
+    fn test_ghost() {
+        let input = example!(
+            "
+            22A = (22B, XXX)
+            22B = (22C, 22C)
+            22C = (22Z, 22Z)
+            22Z = (22B, 22B)
+            XXX = (XXX, XXX)
+            "
         );
-        assert_eq!(result, "6".to_string());
+        let network = Network::new(input.lines()).unwrap();
+        let start = network.names.get("22A").unwrap();
+        let ghost = Ghost::new(start, &Directions::new("LR"), &network);
+        assert_eq!(
+            ghost,
+            Ghost {
+                prefix: vec![],
+                cycle_start: 1,
+                cycle: vec![3, 6],
+                period: 6,
+            }
+        );
+        assert!(ghost.hits(9));
+        assert!(!ghost.hits(10));
+    }
+
+    #[test]
+    fn test_unaligned_cycles() {
+        // The first ghost's first end node is at step 1, but its cycle only
+        // comes back to it every 3 steps after that, so the first ghost is on
+        // an end at steps 1, 4, 7, ... and the second at 2, 4, 6, ...
+        let result = process(example!(
+            "
+            L
+
+            11A = (11Z, 11Z)
+            11Z = (11B, 11B)
+            11B = (11C, 11C)
+            11C = (11Z, 11Z)
+            22A = (22B, 22B)
+            22B = (22Z, 22Z)
+            22Z = (22B, 22B)
+            "
+        ));
+        assert_eq!(result, "4".to_string());
+    }
+
+    #[test]
+    fn test_prefix_hit() {
+        // The second ghost only passes an end node once, on its way into a
+        // cycle without one
+        let result = process(example!(
+            "
+            L
+
+            11A = (11Z, 11Z)
+            11Z = (11Z, 11Z)
+            22A = (22Z, 22Z)
+            22Z = (22B, 22B)
+            22B = (22B, 22B)
+            "
+        ));
+        assert_eq!(result, "1".to_string());
+    }
+
+    #[test]
+    fn test_first_common_end() {
+        let ghost = |cycle_start, cycle: &[u64], period| Ghost {
+            prefix: vec![],
+            cycle_start,
+            cycle: cycle.to_vec(),
+            period,
+        };
+
+        // Aligned ghosts take the least common multiple
+        let ghosts = [ghost(1, &[4], 4), ghost(2, &[6], 6)];
+        assert_eq!(first_common_end(&ghosts), Some(12));
+
+        // One ghost is on an end at odd steps and the other at even steps
+        let ghosts = [ghost(1, &[1], 2), ghost(1, &[2], 2)];
+        assert_eq!(first_common_end(&ghosts), None);
+
+        // Steps 5, 12, 19, ... and 3, 8, 13, 18, 23, 28, 33, 38, ...
+        let ghosts = [ghost(0, &[5], 7), ghost(3, &[3], 5)];
+        assert_eq!(first_common_end(&ghosts), Some(33));
+
+        assert_eq!(crt(2, 4, 4, 6), Some((10, 12)));
+        assert_eq!(crt(1, 4, 2, 6), None);
     }
 }
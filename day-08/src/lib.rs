@@ -0,0 +1,293 @@
+use std::error::Error;
+use std::fmt::{self, Write};
+use std::mem;
+
+use aoc_utils::{Interner, Tokenizer, UnionFind};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Direction {
+    Left = 0,
+    Right = 1,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Node<'a> {
+    pub name: &'a str,
+    pub left: &'a str,
+    pub right: &'a str,
+}
+
+/// A node that's named as a neighbour but has no line of its own.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UndefinedNode(pub String);
+
+impl fmt::Display for UndefinedNode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "node {} is a neighbour but is never defined", self.0)
+    }
+}
+
+impl Error for UndefinedNode {}
+
+pub struct Network<'a> {
+    pub names: Interner<'a>,
+    // The left and right neighbors of each node, indexed by node id
+    pub nodes: Vec<[u32; 2]>,
+    // Whether each node is an end node, indexed by node id
+    ends: Vec<bool>,
+}
+
+impl<'a> Network<'a> {
+    /// Builds the network, checking that every node named as a neighbour
+    /// has a line of its own.
+    pub fn new(lines: impl Iterator<Item = &'a str>) -> Result<Self, UndefinedNode> {
+        let mut names = Interner::new();
+        let mut nodes: Vec<Option<[u32; 2]>> = Vec::new();
+        for line in lines {
+            let node = parse_node(line);
+            let id = names.intern(node.name) as usize;
+            let left = names.intern(node.left);
+            let right = names.intern(node.right);
+            nodes.resize(names.len(), None);
+            nodes[id] = Some([left, right]);
+        }
+        let nodes = nodes
+            .into_iter()
+            .enumerate()
+            .map(|(id, node)| node.ok_or_else(|| UndefinedNode(names.name(id as u32).to_string())))
+            .collect::<Result<Vec<[u32; 2]>, UndefinedNode>>()?;
+        let ends = names.iter().map(|(_, name)| name.ends_with('Z')).collect();
+        Ok(Network { names, nodes, ends })
+    }
+
+    #[inline(always)]
+    pub fn next(&self, node: u32, direction: Direction) -> u32 {
+        self.nodes[node as usize][direction as usize]
+    }
+
+    #[inline(always)]
+    pub fn is_end(&self, node: u32) -> bool {
+        self.ends[node as usize]
+    }
+
+    pub fn start_nodes(&self) -> Vec<u32> {
+        self.names
+            .iter()
+            .filter(|(_, name)| name.ends_with('A'))
+            .map(|(id, _)| id)
+            .collect()
+    }
+
+    /// Returns whether any path leads from `start` to `end`, whichever way
+    /// the directions go.
+    pub fn reachable(&self, start: u32, end: u32) -> bool {
+        let mut seen = vec![false; self.nodes.len()];
+        let mut stack = vec![start];
+        while let Some(node) = stack.pop() {
+            if node == end {
+                return true;
+            }
+            if !mem::replace(&mut seen[node as usize], true) {
+                stack.extend(self.nodes[node as usize]);
+            }
+        }
+        false
+    }
+
+    /// Writes the network in Graphviz's DOT language, for drawing with
+    /// `dot -Tsvg`. Edges are labelled with the directions that take them,
+    /// start nodes are green and end nodes are red. With `clusters`, each
+    /// group of connected nodes is drawn in its own box, labelled with the
+    /// start nodes in it, which shows which parts of the network each ghost
+    /// can wander through.
+    pub fn to_dot(&self, clusters: bool) -> String {
+        let mut dot = String::from("digraph network {\n");
+
+        let components: Vec<Vec<usize>> = if clusters {
+            let mut union_find = UnionFind::new(self.nodes.len());
+            for (id, neighbors) in self.nodes.iter().enumerate() {
+                for &neighbor in neighbors {
+                    union_find.union(id, neighbor as usize);
+                }
+            }
+            union_find.components()
+        } else {
+            vec![(0..self.nodes.len()).collect()]
+        };
+        for (index, component) in components.iter().enumerate() {
+            let mut indent = "    ";
+            if clusters {
+                let starts: Vec<&str> = component
+                    .iter()
+                    .map(|&id| self.names.name(id as u32))
+                    .filter(|name| name.ends_with('A'))
+                    .collect();
+                writeln!(dot, "    subgraph cluster_{index} {{").unwrap();
+                writeln!(dot, "        label=\"{}\";", starts.join(", ")).unwrap();
+                indent = "        ";
+            }
+            for &id in component.iter() {
+                let name = self.names.name(id as u32);
+                if name.ends_with('A') {
+                    writeln!(
+                        dot,
+                        "{indent}\"{name}\" [style=filled, fillcolor=palegreen];"
+                    )
+                } else if self.is_end(id as u32) {
+                    writeln!(
+                        dot,
+                        "{indent}\"{name}\" [style=filled, fillcolor=lightcoral];"
+                    )
+                } else {
+                    writeln!(dot, "{indent}\"{name}\";")
+                }
+                .unwrap();
+            }
+            if clusters {
+                dot.push_str("    }\n");
+            }
+        }
+
+        for (id, &[left, right]) in self.nodes.iter().enumerate() {
+            let name = self.names.name(id as u32);
+            let mut edge = |to: u32, label: &str| {
+                let to = self.names.name(to);
+                writeln!(dot, "    \"{name}\" -> \"{to}\" [label=\"{label}\"];").unwrap();
+            };
+            if left == right {
+                edge(left, "L,R");
+            } else {
+                edge(left, "L");
+                edge(right, "R");
+            }
+        }
+
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+pub fn parse_node(line: &str) -> Node<'_> {
+    let mut tokens = Tokenizer::new(line);
+
+    let name = tokens.get();
+    tokens.consume("=");
+    tokens.consume("(");
+    let left = tokens.get();
+    tokens.consume(",");
+    let right = tokens.get();
+    tokens.consume(")");
+
+    Node { name, left, right }
+}
+
+#[cfg(test)]
+mod tests {
+    use aoc_utils::example;
+
+    use super::*;
+    use Direction::*;
+
+    #[test]
+    fn test_parse_node() {
+        let line = "AAA = (BBB, CCC)";
+        let value = parse_node(line);
+        assert_eq!(value.name, "AAA");
+        assert_eq!(value.left, "BBB");
+        assert_eq!(value.right, "CCC");
+    }
+
+    #[test]
+    fn test_network() {
+        let input = example!(
+            "
+            AAA = (BBB, BBB)
+            BBB = (AAA, ZZZ)
+            ZZZ = (ZZZ, ZZZ)
+            "
+        );
+        let network = Network::new(input.lines()).unwrap();
+        let aaa = network.names.get("AAA").unwrap();
+        let bbb = network.names.get("BBB").unwrap();
+        let zzz = network.names.get("ZZZ").unwrap();
+        assert_eq!(network.next(aaa, Left), bbb);
+        assert_eq!(network.next(bbb, Left), aaa);
+        assert_eq!(network.next(bbb, Right), zzz);
+        assert_eq!(network.next(zzz, Right), zzz);
+        assert!(network.reachable(aaa, zzz));
+        assert!(!network.reachable(zzz, aaa));
+    }
+
+    #[test]
+    fn test_undefined_node() {
+        let input = example!(
+            "
+            AAA = (BBB, ZZZ)
+            BBB = (AAA, AAA)
+            "
+        );
+        assert_eq!(
+            Network::new(input.lines()).err(),
+            Some(UndefinedNode("ZZZ".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_to_dot() {
+        let input = example!(
+            "
+            11A = (11B, XXX)
+            11B = (XXX, 11Z)
+            11Z = (11B, XXX)
+            XXX = (XXX, XXX)
+            22A = (22Z, 22Z)
+            22Z = (22A, 22A)
+            "
+        );
+        let network = Network::new(input.lines()).unwrap();
+        let dot = example!(
+            r#"
+            digraph network {
+                "11A" [style=filled, fillcolor=palegreen];
+                "11B";
+                "XXX";
+                "11Z" [style=filled, fillcolor=lightcoral];
+                "22A" [style=filled, fillcolor=palegreen];
+                "22Z" [style=filled, fillcolor=lightcoral];
+                "11A" -> "11B" [label="L"];
+                "11A" -> "XXX" [label="R"];
+                "11B" -> "XXX" [label="L"];
+                "11B" -> "11Z" [label="R"];
+                "XXX" -> "XXX" [label="L,R"];
+                "11Z" -> "11B" [label="L"];
+                "11Z" -> "XXX" [label="R"];
+                "22A" -> "22Z" [label="L,R"];
+                "22Z" -> "22A" [label="L,R"];
+            }
+            "#
+        );
+        assert_eq!(network.to_dot(false), dot);
+
+        let dot = network.to_dot(true);
+        let clusters: Vec<&str> = dot.lines().take(14).collect();
+        assert_eq!(
+            clusters,
+            [
+                "digraph network {",
+                "    subgraph cluster_0 {",
+                "        label=\"11A\";",
+                "        \"11A\" [style=filled, fillcolor=palegreen];",
+                "        \"11B\";",
+                "        \"XXX\";",
+                "        \"11Z\" [style=filled, fillcolor=lightcoral];",
+                "    }",
+                "    subgraph cluster_1 {",
+                "        label=\"22A\";",
+                "        \"22A\" [style=filled, fillcolor=palegreen];",
+                "        \"22Z\" [style=filled, fillcolor=lightcoral];",
+                "    }",
+                "    \"11A\" -> \"11B\" [label=\"L\"];",
+            ]
+        );
+    }
+}
@@ -1,6 +1,9 @@
+use std::io::BufRead;
+
+use aoc_utils::{each_line, run};
+
 fn main() {
-    let input = include_str!("./input.txt");
-    let output = process(input);
+    let output = run(include_str!("./input.txt"), process_reader);
     dbg!(output);
 }
 
@@ -44,12 +47,11 @@ impl History {
     }
 }
 
-fn process(input: &str) -> String {
-    let value: i64 = input
-        .lines()
-        .map(|line| parse_line(line))
-        .map(|history| process_history(history))
-        .sum();
+fn process_reader(reader: impl BufRead) -> String {
+    let mut value: i64 = 0;
+    each_line(reader, |line| {
+        value += process_history(parse_line(line));
+    });
     value.to_string()
 }
 
@@ -67,15 +69,23 @@ fn process_history(values: Vec<i64>) -> i64 {
 
 #[cfg(test)]
 mod tests {
+    use aoc_utils::example;
+
     use super::*;
 
+    fn process(input: &str) -> String {
+        process_reader(input.as_bytes())
+    }
+
     #[test]
     fn test_process() {
-        let result = process(
-            "0 3 6 9 12 15
-             1 3 6 10 15 21
-             10 13 16 21 30 45",
-        );
+        let result = process(example!(
+            "
+            0 3 6 9 12 15
+            1 3 6 10 15 21
+            10 13 16 21 30 45
+            "
+        ));
         assert_eq!(result, "114".to_string());
     }
 }
@@ -0,0 +1,93 @@
+use day_10::Map;
+
+fn main() {
+    let input = include_str!("./input.txt");
+    let output = process(input);
+    dbg!(output);
+}
+
+fn process(input: &str) -> String {
+    let map = Map::new(input);
+    map.count_enclosed().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use aoc_utils::example;
+
+    use super::*;
+
+    #[test]
+    fn test_process() {
+        let result = process(example!(
+            "
+            ...........
+            .S-------7.
+            .|F-----7|.
+            .||.....||.
+            .||.....||.
+            .|L-7.F-J|.
+            .|..|.|..|.
+            .L--J.L--J.
+            ...........
+            "
+        ));
+        assert_eq!(result, "4".to_string());
+    }
+
+    #[test]
+    fn test_squeezing_between_pipes() {
+        let result = process(example!(
+            "
+            ..........
+            .S------7.
+            .|F----7|.
+            .||....||.
+            .||....||.
+            .|L-7F-J|.
+            .|..||..|.
+            .L--JL--J.
+            ..........
+            "
+        ));
+        assert_eq!(result, "4".to_string());
+    }
+
+    #[test]
+    fn test_larger_example() {
+        let result = process(example!(
+            "
+            .F----7F7F7F7F-7....
+            .|F--7||||||||FJ....
+            .||.FJ||||||||L7....
+            FJL7L7LJLJ||LJ.L-7..
+            L--J.L7...LJS7F-7L7.
+            ....F-J..F7FJ|L7L7L7
+            ....L7.F7||L7|.L7L7|
+            .....|FJLJ|FJ|F7|.LJ
+            ....FJL-7.||.||||...
+            ....L---J.LJ.LJLJ...
+            "
+        ));
+        assert_eq!(result, "8".to_string());
+    }
+
+    #[test]
+    fn test_junk_pipes() {
+        let result = process(example!(
+            "
+            FF7FSF7F7F7F7F7F---7
+            L|LJ||||||||||||F--J
+            FL-7LJLJ||||||LJL-77
+            F--JF--7||LJLJ7F7FJ-
+            L---JF-JLJ.||-FJLJJ7
+            |F|F-JF---7F7-L7L|7|
+            |FFJF7L7F-JF7|JL---7
+            7-L-JL7||F7|L7F-7F7|
+            L.L7LFJ|||||FJL7||LJ
+            L7JLJL-JLJLJL--JLJ.L
+            "
+        ));
+        assert_eq!(result, "10".to_string());
+    }
+}
@@ -0,0 +1,18 @@
+use std::env;
+use std::io::{self, IsTerminal};
+
+use day_10::{Map, Style};
+
+// Prints the map, with colours if it's going to a terminal. Pass --plain or
+// --ansi to choose.
+fn main() {
+    let style = match env::args().nth(1).as_deref() {
+        Some("--plain") => Style::Plain,
+        Some("--ansi") => Style::Ansi,
+        Some(arg) => panic!("unknown argument: {arg}"),
+        None if io::stdout().is_terminal() => Style::Ansi,
+        None => Style::Plain,
+    };
+    let map = Map::new(include_str!("./input.txt"));
+    print!("{}", map.render(style));
+}
@@ -0,0 +1,601 @@
+use std::error::Error;
+use std::fmt;
+
+mod render;
+
+pub use render::Style;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Direction {
+    North,
+    South,
+    East,
+    West,
+}
+
+impl Direction {
+    const ALL: [Direction; 4] = [
+        Direction::North,
+        Direction::South,
+        Direction::East,
+        Direction::West,
+    ];
+
+    fn opposite(&self) -> Self {
+        use Direction::*;
+        match self {
+            North => South,
+            South => North,
+            East => West,
+            West => East,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Pipe {
+    NorthSouth,
+    EastWest,
+    NorthEast,
+    NorthWest,
+    SouthWest,
+    SouthEast,
+    // Think of the ground as a pipe that doesn't connect to anything
+    Ground,
+}
+
+impl Pipe {
+    // Every pipe that connects two tiles
+    const CONNECTING: [Pipe; 6] = [
+        Pipe::NorthSouth,
+        Pipe::EastWest,
+        Pipe::NorthEast,
+        Pipe::NorthWest,
+        Pipe::SouthWest,
+        Pipe::SouthEast,
+    ];
+
+    pub fn new(c: char) -> Pipe {
+        use Pipe::*;
+        match c {
+            '|' => NorthSouth,
+            '-' => EastWest,
+            'L' => NorthEast,
+            'J' => NorthWest,
+            '7' => SouthWest,
+            'F' => SouthEast,
+            '.' => Ground,
+            _ => panic!("unknown pipe: '{c}'"),
+        }
+    }
+
+    pub fn opens_to(&self, direction: Direction) -> bool {
+        use Direction::*;
+        use Pipe::*;
+        match *self {
+            NorthSouth => match direction {
+                North | South => true,
+                East | West => false,
+            },
+            EastWest => match direction {
+                East | West => true,
+                North | South => false,
+            },
+            NorthEast => match direction {
+                North | East => true,
+                South | West => false,
+            },
+            NorthWest => match direction {
+                North | West => true,
+                South | East => false,
+            },
+            SouthWest => match direction {
+                South | West => true,
+                North | East => false,
+            },
+            SouthEast => match direction {
+                South | East => true,
+                North | West => false,
+            },
+            Ground => false,
+        }
+    }
+}
+
+trait Location<T> {
+    fn neighbor(&self, direction: Direction) -> T;
+}
+
+impl Location<(isize, isize)> for (isize, isize) {
+    fn neighbor(&self, direction: Direction) -> (isize, isize) {
+        use Direction::*;
+        match direction {
+            North => (self.0 - 1, self.1),
+            South => (self.0 + 1, self.1),
+            East => (self.0, self.1 + 1),
+            West => (self.0, self.1 - 1),
+        }
+    }
+}
+
+/// A tile of the main loop, with the start replaced by the pipe it stands for.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LoopTile {
+    pub row: usize,
+    pub col: usize,
+    pub pipe: Pipe,
+}
+
+/// The main loop, as its tiles in the order they're walked from the start.
+/// The start is the first tile, and the last tile connects back to it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PipeLoop {
+    pub tiles: Vec<LoopTile>,
+}
+
+impl PipeLoop {
+    pub fn len(&self) -> usize {
+        self.tiles.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tiles.is_empty()
+    }
+
+    /// Returns the number of steps along the loop from the start to the
+    /// farthest tile.
+    pub fn distance(&self) -> usize {
+        self.tiles.len() / 2
+    }
+
+    /// Returns the tiles farthest from the start along the loop: one tile if
+    /// the loop has an even length, which is always the case on a square
+    /// grid, or the two tiles that tie if it's odd.
+    pub fn farthest(&self) -> Vec<LoopTile> {
+        let half = self.tiles.len() / 2;
+        if self.tiles.len().is_multiple_of(2) {
+            vec![self.tiles[half]]
+        } else {
+            vec![self.tiles[half], self.tiles[half + 1]]
+        }
+    }
+
+    /// Returns the area of the polygon through the centres of the loop's
+    /// tiles, using the shoelace formula.
+    pub fn area(&self) -> usize {
+        let twice_area: isize = self
+            .tiles
+            .iter()
+            .zip(self.tiles.iter().cycle().skip(1))
+            .map(|(a, b)| a.col as isize * b.row as isize - b.col as isize * a.row as isize)
+            .sum();
+        twice_area.unsigned_abs() / 2
+    }
+}
+
+/// Why the start couldn't be resolved into a pipe.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum StartError {
+    /// There's no `S` on the map
+    Missing,
+    /// No pipe at the start would close a loop
+    NoLoop,
+    /// Each of these pipes at the start would close a loop
+    Ambiguous(Vec<Pipe>),
+}
+
+impl fmt::Display for StartError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StartError::Missing => write!(f, "the map has no start"),
+            StartError::NoLoop => write!(f, "no pipe at the start closes a loop"),
+            StartError::Ambiguous(pipes) => {
+                write!(f, "several pipes at the start close a loop: {pipes:?}")
+            }
+        }
+    }
+}
+
+impl Error for StartError {}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Side {
+    Loop,
+    Inside,
+    Outside,
+}
+
+pub struct Map {
+    map: Vec<Vec<Pipe>>,
+    start_row: usize,
+    start_col: usize,
+}
+
+impl Map {
+    pub fn new(input: &str) -> Map {
+        Map::try_new(input).unwrap_or_else(|error| panic!("{error}"))
+    }
+
+    /// Builds the map, working out which pipe the start stands for by
+    /// trying each one and keeping the one that closes a loop. Neighbours
+    /// that happen to open towards the start without being on the loop are
+    /// ignored.
+    pub fn try_new(input: &str) -> Result<Map, StartError> {
+        // Build the map of the pipes
+        let mut map: Vec<Vec<Pipe>> = Vec::new();
+        let mut start = None;
+        for (row, line) in input.lines().enumerate() {
+            map.push(Vec::new());
+            for (col, c) in line.chars().enumerate() {
+                let pipe = if c == 'S' {
+                    start = Some((row, col));
+                    Pipe::new('.') // for now
+                } else {
+                    Pipe::new(c)
+                };
+                map[row].push(pipe);
+            }
+        }
+        let (start_row, start_col) = start.ok_or(StartError::Missing)?;
+        let mut map = Map {
+            map,
+            start_row,
+            start_col,
+        };
+
+        // Figure out what kind of pipe the start is
+        let start = (start_row as isize, start_col as isize);
+        let mut candidates: Vec<Pipe> = Vec::new();
+        for pipe in Pipe::CONNECTING {
+            map.map[start_row][start_col] = pipe;
+            if map.walk(start).is_some() {
+                candidates.push(pipe);
+            }
+        }
+        match candidates[..] {
+            [pipe] => map.map[start_row][start_col] = pipe,
+            [] => return Err(StartError::NoLoop),
+            _ => return Err(StartError::Ambiguous(candidates)),
+        }
+
+        Ok(map)
+    }
+
+    fn pipe_at(&self, coords: (isize, isize)) -> Pipe {
+        use Pipe::*;
+        let (row, col) = coords;
+        if row < 0 || row >= self.map.len() as isize {
+            return Ground;
+        }
+        if col < 0 || col >= self.map[row as usize].len() as isize {
+            return Ground;
+        }
+        self.map[row as usize][col as usize]
+    }
+
+    fn can_move(&self, location: (isize, isize), direction: Direction) -> bool {
+        if !self.pipe_at(location).opens_to(direction) {
+            return false;
+        }
+        if !self
+            .pipe_at(location.neighbor(direction))
+            .opens_to(direction.opposite())
+        {
+            return false;
+        }
+        true
+    }
+
+    /// Walks the loop from the start and returns its tiles in order.
+    pub fn find_loop(&self) -> PipeLoop {
+        let start = (self.start_row as isize, self.start_col as isize);
+        match self.walk(start) {
+            Some(tiles) => PipeLoop { tiles },
+            None => panic!("no loop through the start: {:?}", start),
+        }
+    }
+
+    // Follows the pipes from `start` until they lead back to it, and returns
+    // the tiles visited, or None if they lead anywhere else. A pipe has only
+    // two ends, so the walk can't get stuck going round a loop that doesn't
+    // include the start.
+    fn walk(&self, start: (isize, isize)) -> Option<Vec<LoopTile>> {
+        let opens_to = |location, direction| self.pipe_at(location).opens_to(direction);
+
+        let mut location = start;
+        let mut direction = Direction::ALL
+            .into_iter()
+            .find(|&direction| opens_to(start, direction))?;
+        let mut tiles = vec![self.loop_tile(start)];
+        loop {
+            if !self.can_move(location, direction) {
+                return None;
+            }
+            location = location.neighbor(direction);
+            if location == start {
+                return Some(tiles);
+            }
+            tiles.push(self.loop_tile(location));
+
+            // Leave by the end we didn't come in by
+            let came_from = direction.opposite();
+            direction = Direction::ALL
+                .into_iter()
+                .find(|&direction| direction != came_from && opens_to(location, direction))?;
+        }
+    }
+
+    fn loop_tile(&self, location: (isize, isize)) -> LoopTile {
+        LoopTile {
+            row: location.0 as usize,
+            col: location.1 as usize,
+            pipe: self.pipe_at(location),
+        }
+    }
+
+    pub fn calculate_distance(&self) -> u32 {
+        self.find_loop().distance() as u32
+    }
+
+    /// Counts the tiles enclosed by the loop. Any tile that isn't part of
+    /// the loop counts, including junk pipes.
+    pub fn count_enclosed(&self) -> usize {
+        self.sides()
+            .iter()
+            .flatten()
+            .filter(|&&side| side == Side::Inside)
+            .count()
+    }
+
+    // Works out where each tile is relative to the loop
+    fn sides(&self) -> Vec<Vec<Side>> {
+        let mut sides: Vec<Vec<Side>> = self
+            .map
+            .iter()
+            .map(|row| vec![Side::Outside; row.len()])
+            .collect();
+        for tile in self.find_loop().tiles {
+            sides[tile.row][tile.col] = Side::Loop;
+        }
+
+        // Scan each row just above the middle of its tiles. Only loop pipes
+        // that reach north cross that line, so a run like L-7 counts as one
+        // crossing and L-J as none, which covers squeezing between pipes.
+        for (row, pipes) in self.map.iter().enumerate() {
+            let mut inside = false;
+            for (col, pipe) in pipes.iter().enumerate() {
+                if sides[row][col] == Side::Loop {
+                    if pipe.opens_to(Direction::North) {
+                        inside = !inside;
+                    }
+                } else if inside {
+                    sides[row][col] = Side::Inside;
+                }
+            }
+        }
+        sides
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use aoc_utils::example;
+
+    use super::*;
+    use Pipe::*;
+
+    #[test]
+    fn test_find_start_pipe() {
+        let input = example!(
+            "
+            .....
+            .S-7.
+            .|.|.
+            .L-J.
+            .....
+            "
+        );
+        let map = Map::new(input);
+        let start = map.map[map.start_row][map.start_col];
+        assert_eq!(start, SouthEast);
+
+        let input = example!(
+            "
+            .....
+            .F-7.
+            .|.|.
+            .S-J.
+            .....
+            "
+        );
+        let map = Map::new(input);
+        let start = map.map[map.start_row][map.start_col];
+        assert_eq!(start, NorthEast);
+
+        let input = example!(
+            "
+            .....
+            .F-7.
+            .|.|.
+            .L-S.
+            .....
+            "
+        );
+        let map = Map::new(input);
+        let start = map.map[map.start_row][map.start_col];
+        assert_eq!(start, NorthWest);
+
+        let input = example!(
+            "
+            .....
+            .F-S.
+            .|.|.
+            .L-J.
+            .....
+            "
+        );
+        let map = Map::new(input);
+        let start = map.map[map.start_row][map.start_col];
+        assert_eq!(start, SouthWest);
+
+        let input = example!(
+            "
+            .....
+            .FS7.
+            .|.|.
+            .L-J.
+            .....
+            "
+        );
+        let map = Map::new(input);
+        let start = map.map[map.start_row][map.start_col];
+        assert_eq!(start, EastWest);
+
+        let input = example!(
+            "
+            .....
+            .F-7.
+            .|.S.
+            .L-J.
+            .....
+            "
+        );
+        let map = Map::new(input);
+        let start = map.map[map.start_row][map.start_col];
+        assert_eq!(start, NorthSouth);
+    }
+
+    #[test]
+    fn test_start_with_decoy_neighbours() {
+        // Every neighbour opens towards the start, but only two of them are
+        // on the loop
+        let input = example!(
+            "
+            .|...
+            -S-7.
+            .|.|.
+            .L-J.
+            .....
+            "
+        );
+        let map = Map::new(input);
+        let start = map.map[map.start_row][map.start_col];
+        assert_eq!(start, SouthEast);
+    }
+
+    #[test]
+    fn test_start_errors() {
+        // The start's neighbours connect to it, but not to each other
+        let input = example!(
+            "
+            .....
+            .-S-.
+            .|.|.
+            .L-J.
+            .....
+            "
+        );
+        assert_eq!(Map::try_new(input).err(), Some(StartError::NoLoop));
+
+        let input = example!(
+            "
+            .....
+            .F-|.
+            .|.S.
+            .L-|.
+            .....
+            "
+        );
+        assert_eq!(Map::try_new(input).err(), Some(StartError::NoLoop));
+
+        let input = example!(
+            "
+            F-7..
+            |.|..
+            L-S-7
+            ..|.|
+            ..L-J
+            "
+        );
+        assert_eq!(
+            Map::try_new(input).err(),
+            Some(StartError::Ambiguous(vec![NorthWest, SouthEast]))
+        );
+
+        assert_eq!(Map::try_new("..\n..").err(), Some(StartError::Missing));
+    }
+
+    #[test]
+    fn test_find_loop() {
+        let input = example!(
+            "
+            -L|F7
+            7S-7|
+            L|7||
+            -L-J|
+            L|-JF
+            "
+        );
+        let pipe_loop = Map::new(input).find_loop();
+        let tiles: Vec<(usize, usize, Pipe)> = pipe_loop
+            .tiles
+            .iter()
+            .map(|tile| (tile.row, tile.col, tile.pipe))
+            .collect();
+        assert_eq!(
+            tiles,
+            vec![
+                (1, 1, SouthEast),
+                (2, 1, NorthSouth),
+                (3, 1, NorthEast),
+                (3, 2, EastWest),
+                (3, 3, NorthWest),
+                (2, 3, NorthSouth),
+                (1, 3, SouthWest),
+                (1, 2, EastWest),
+            ]
+        );
+        assert_eq!(pipe_loop.distance(), 4);
+        assert_eq!(
+            pipe_loop.farthest(),
+            vec![LoopTile {
+                row: 3,
+                col: 3,
+                pipe: NorthWest
+            }]
+        );
+        assert_eq!(pipe_loop.area(), 4);
+    }
+
+    #[test]
+    fn test_farthest_odd_length() {
+        let tile = |row, col| LoopTile {
+            row,
+            col,
+            pipe: Ground,
+        };
+        let pipe_loop = PipeLoop {
+            tiles: vec![tile(0, 0), tile(0, 1), tile(1, 1), tile(1, 0), tile(2, 0)],
+        };
+        assert_eq!(pipe_loop.farthest(), vec![tile(1, 1), tile(1, 0)]);
+    }
+
+    #[test]
+    fn test_area_matches_enclosed_count() {
+        // By Pick's theorem the tiles inside the loop number the area, less
+        // half the loop's length, plus one
+        let input = example!(
+            "
+            ..F7.
+            .FJ|.
+            SJ.L7
+            |F--J
+            LJ...
+            "
+        );
+        let map = Map::new(input);
+        let pipe_loop = map.find_loop();
+        let inside = pipe_loop.area() + 1 - pipe_loop.len() / 2;
+        assert_eq!(inside, map.count_enclosed());
+    }
+}
@@ -0,0 +1,141 @@
+use crate::{Map, Pipe, Side};
+
+/// How `Map::render` draws the map.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Style {
+    /// Plain text. Tiles that aren't on the loop are drawn as `I` if they're
+    /// inside it and as `O` if they're outside, except junk pipes outside
+    /// the loop, which keep their shape.
+    Plain,
+    /// Text with ANSI escape codes for a terminal. Every tile keeps its
+    /// shape, the loop is bold, junk pipes are dimmed and the tiles inside
+    /// the loop have a green background.
+    Ansi,
+}
+
+const RESET: &str = "\x1b[0m";
+const BOLD: &str = "\x1b[1m";
+const DIM: &str = "\x1b[2m";
+const START: &str = "\x1b[1;33m";
+const INSIDE: &str = "\x1b[42m";
+
+impl Pipe {
+    // Loop pipes get double lines so they stand out from the junk
+    fn glyph(&self, on_loop: bool) -> char {
+        use Pipe::*;
+        match (self, on_loop) {
+            (NorthSouth, true) => '║',
+            (EastWest, true) => '═',
+            (NorthEast, true) => '╚',
+            (NorthWest, true) => '╝',
+            (SouthWest, true) => '╗',
+            (SouthEast, true) => '╔',
+            (NorthSouth, false) => '│',
+            (EastWest, false) => '─',
+            (NorthEast, false) => '└',
+            (NorthWest, false) => '┘',
+            (SouthWest, false) => '┐',
+            (SouthEast, false) => '┌',
+            (Ground, _) => '·',
+        }
+    }
+}
+
+impl Map {
+    /// Draws the map with box-drawing characters, picking out the start,
+    /// the loop, and which side of it every other tile is on.
+    pub fn render(&self, style: Style) -> String {
+        let sides = self.sides();
+        let mut output = String::new();
+        for (row, pipes) in self.map.iter().enumerate() {
+            for (col, pipe) in pipes.iter().enumerate() {
+                let side = sides[row][col];
+                let is_start = (row, col) == (self.start_row, self.start_col);
+                match style {
+                    Style::Plain => output.push(match side {
+                        _ if is_start => 'S',
+                        Side::Loop => pipe.glyph(true),
+                        Side::Inside => 'I',
+                        Side::Outside if *pipe == Pipe::Ground => 'O',
+                        Side::Outside => pipe.glyph(false),
+                    }),
+                    Style::Ansi => {
+                        let (code, glyph) = match side {
+                            _ if is_start => (START, 'S'),
+                            Side::Loop => (BOLD, pipe.glyph(true)),
+                            Side::Inside => (INSIDE, pipe.glyph(false)),
+                            Side::Outside => (DIM, pipe.glyph(false)),
+                        };
+                        output.push_str(code);
+                        output.push(glyph);
+                        output.push_str(RESET);
+                    }
+                }
+            }
+            output.push('\n');
+        }
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use aoc_utils::example;
+
+    use super::*;
+
+    #[test]
+    fn test_render_plain() {
+        let input = example!(
+            "
+            -L|F7
+            7S-7|
+            L|7||
+            -L-J|
+            L|-JF
+            "
+        );
+        let output = example!(
+            "
+            ─└│┌┐
+            ┐S═╗│
+            └║I║│
+            ─╚═╝│
+            └│─┘┌
+            "
+        );
+        assert_eq!(Map::new(input).render(Style::Plain), output);
+    }
+
+    #[test]
+    fn test_render_ansi() {
+        let map = Map::new("S7\nLJ");
+        let output = map.render(Style::Ansi);
+        assert_eq!(
+            output.lines().next().unwrap(),
+            "\x1b[1;33mS\x1b[0m\x1b[1m╗\x1b[0m"
+        );
+
+        // Without the escape codes it's the same as the plain text, apart
+        // from the tiles that plain text marks as inside or outside
+        let map = Map::new(example!(
+            "
+            .....
+            .S-7.
+            .|.|.
+            .L-J.
+            .....
+            "
+        ));
+        let stripped = map
+            .render(Style::Ansi)
+            .split('\x1b')
+            .map(|part| part.split_once('m').map_or(part, |(_, text)| text))
+            .collect::<String>();
+        assert_eq!(stripped, "·····\n·S═╗·\n·║·║·\n·╚═╝·\n·····\n");
+        assert_eq!(
+            map.render(Style::Plain),
+            "OOOOO\nOS═╗O\nO║I║O\nO╚═╝O\nOOOOO\n"
+        );
+    }
+}